@@ -0,0 +1,367 @@
+//! Watching the simulations run. A day's simulation hands over a [`Frame`] at each step, and an
+//! [`Animation`] decides which of them to show, how much of each fits on screen and how fast they
+//! go -- or, when headless, just keeps them.
+
+use std::{
+    io::{self, BufRead, Write},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::ParseError,
+    grid::{Grid, Pos},
+    image::Palette,
+};
+
+/// What a simulation looks like at one moment.
+pub struct Frame {
+    pub picture: Grid<char>,
+    /// Where the action is, which the viewport keeps in sight.
+    pub focus: Option<Pos>,
+}
+
+pub trait Simulation {
+    /// Moves on one step, or gives `false` (and leaves everything as it was) once there's nothing
+    /// left to do.
+    fn step(&mut self) -> bool;
+    fn frame(&self) -> Frame;
+    /// Whether `step` never runs out, so only a step limit or someone quitting stops it.
+    fn endless(&self) -> bool {
+        false
+    }
+    /// The colours the characters of a frame get when it's saved as an image.
+    fn palette(&self) -> Palette {
+        Palette::DEFAULT
+    }
+}
+
+/// Parses a day's input into a simulation, ready to start from the beginning.
+pub type Animate = fn(&str) -> Result<Box<dyn Simulation>, ParseError>;
+
+pub const DEFAULT_FPS: u32 = 30;
+pub const DEFAULT_VIEWPORT: (usize, usize) = (80, 24);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// How many frames to show a second, 0 for as fast as they can be drawn.
+    pub fps: u32,
+    /// Only every `every`th step is drawn, for simulations with a lot of them.
+    pub every: usize,
+    /// The most columns and rows of a frame shown at once.
+    pub viewport: (usize, usize),
+    /// Stop after this many steps, for simulations that would go on forever.
+    pub limit: Option<usize>,
+    /// Start paused, waiting to be stepped through.
+    pub paused: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            every: 1,
+            viewport: DEFAULT_VIEWPORT,
+            limit: None,
+            paused: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Whether playing `simulation` comes to an end without anyone there to quit it, as it has to
+    /// when the frames are being kept rather than watched.
+    pub fn stops(&self, simulation: &dyn Simulation) -> bool {
+        self.limit.is_some() || !simulation.endless()
+    }
+}
+
+/// The part of a frame that's on screen. It only scrolls once the focus gets near an edge, so
+/// the picture doesn't jitter around with every step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub width: usize,
+    pub height: usize,
+    /// The top left of what's shown.
+    pub corner: Pos,
+}
+
+impl Viewport {
+    pub fn new((width, height): (usize, usize)) -> Self {
+        Self {
+            width,
+            height,
+            corner: (0, 0),
+        }
+    }
+
+    /// Scrolls along one axis so `focus` is at least `margin` in from either end, without going
+    /// past the end of the picture.
+    fn follow_axis(corner: usize, view: usize, picture: usize, focus: usize) -> usize {
+        if picture <= view {
+            return 0;
+        }
+        let margin = view / 4;
+        let corner = if focus < corner + margin {
+            focus.saturating_sub(margin)
+        } else if focus + margin >= corner + view {
+            focus + margin + 1 - view
+        } else {
+            corner
+        };
+        corner.min(picture - view)
+    }
+
+    pub fn follow(&mut self, frame: &Frame) {
+        let (picture_width, picture_height) = (frame.picture.width(), frame.picture.height());
+        let (x, y) = frame.focus.unwrap_or(self.corner);
+        self.corner = (
+            Self::follow_axis(self.corner.0, self.width, picture_width, x),
+            Self::follow_axis(self.corner.1, self.height, picture_height, y),
+        );
+    }
+
+    /// The part of `picture` in view, one line per row.
+    pub fn render(&self, picture: &Grid<char>) -> String {
+        let (left, top) = self.corner;
+        let right = (left + self.width).min(picture.width());
+        let bottom = (top + self.height).min(picture.height());
+        (top..bottom)
+            .map(|y| {
+                picture.row(y)[left.min(right)..right]
+                    .iter()
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Steps `simulation` along as `settings` say, handing `show` the first frame, every
+/// `settings.every`th one after and the last. `show` gives `false` to stop early. Gives how many
+/// steps were taken.
+pub fn play(
+    simulation: &mut dyn Simulation,
+    settings: &Settings,
+    mut show: impl FnMut(&Frame, usize) -> bool,
+) -> usize {
+    let mut steps = 0;
+    if !show(&simulation.frame(), steps) {
+        return steps;
+    }
+    let mut shown = true;
+    while settings.limit.is_none_or(|limit| steps < limit) {
+        if !simulation.step() {
+            break;
+        }
+        steps += 1;
+        shown = steps.is_multiple_of(settings.every);
+        if shown && !show(&simulation.frame(), steps) {
+            return steps;
+        }
+    }
+    // always finish on how it ended up
+    if !shown {
+        show(&simulation.frame(), steps);
+    }
+    steps
+}
+
+enum Output {
+    Terminal {
+        /// Lines typed while it runs, read on another thread so waiting for them never holds up
+        /// the animation.
+        controls: Receiver<String>,
+        paused: bool,
+        last_frame: Option<Instant>,
+    },
+    Headless(Vec<String>),
+}
+
+pub struct Animation {
+    settings: Settings,
+    viewport: Viewport,
+    output: Output,
+}
+
+const CONTROLS: &str = "enter: pause / step  c: carry on  q: quit";
+
+impl Animation {
+    /// Draws on the terminal, over the top of the last frame each time.
+    pub fn terminal(settings: Settings) -> Self {
+        let (send, controls) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if send.send(line.trim().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            viewport: Viewport::new(settings.viewport),
+            output: Output::Terminal {
+                controls,
+                paused: settings.paused,
+                last_frame: None,
+            },
+            settings,
+        }
+    }
+
+    /// Keeps every frame that would have been drawn instead, with no waiting between them.
+    pub fn headless(settings: Settings) -> Self {
+        Self {
+            viewport: Viewport::new(settings.viewport),
+            output: Output::Headless(Vec::new()),
+            settings,
+        }
+    }
+
+    /// Runs `simulation` to the end, or until the step limit or being told to quit, and gives
+    /// how many steps it took.
+    pub fn run(&mut self, simulation: &mut dyn Simulation) -> usize {
+        let settings = self.settings.clone();
+        play(simulation, &settings, |frame, step| self.show(frame, step))
+    }
+
+    /// The frames a headless animation kept, as they would have been drawn.
+    pub fn frames(&self) -> &[String] {
+        match &self.output {
+            Output::Headless(frames) => frames,
+            Output::Terminal { .. } => &[],
+        }
+    }
+
+    /// Gives `false` if it's time to stop.
+    fn show(&mut self, frame: &Frame, step: usize) -> bool {
+        self.viewport.follow(frame);
+        let picture = self.viewport.render(&frame.picture);
+        let frame_time = match self.settings.fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        };
+        match &mut self.output {
+            Output::Headless(frames) => {
+                frames.push(picture);
+                true
+            }
+            Output::Terminal {
+                controls,
+                paused,
+                last_frame,
+            } => {
+                if let Some(last) = last_frame {
+                    thread::sleep(frame_time.saturating_sub(last.elapsed()));
+                }
+                *last_frame = Some(Instant::now());
+                let state = if *paused { "paused" } else { "running" };
+                // clear the screen and go back to the top left before drawing
+                print!(
+                    "\x1b[2J\x1b[H{}\nstep {} ({})  {}\n",
+                    picture, step, state, CONTROLS
+                );
+                let _ = io::stdout().flush();
+                if *paused {
+                    match controls.recv().as_deref() {
+                        Ok("q") => false,
+                        Ok("c") => {
+                            *paused = false;
+                            true
+                        }
+                        Ok(_) => true,
+                        // nothing more can be typed, so there's no way to step any further
+                        Err(_) => {
+                            *paused = false;
+                            true
+                        }
+                    }
+                } else {
+                    match controls.try_recv().as_deref() {
+                        Ok("q") => false,
+                        Ok(_) => {
+                            *paused = true;
+                            true
+                        }
+                        Err(TryRecvError::Empty | TryRecvError::Disconnected) => true,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A dot walking right along a line.
+    struct Walk {
+        at: usize,
+        len: usize,
+    }
+
+    impl Simulation for Walk {
+        fn step(&mut self) -> bool {
+            if self.at + 1 == self.len {
+                return false;
+            }
+            self.at += 1;
+            true
+        }
+        fn frame(&self) -> Frame {
+            let mut picture = Grid::new(self.len, 1, '.');
+            picture[(self.at, 0)] = '@';
+            Frame {
+                picture,
+                focus: Some((self.at, 0)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_headless() {
+        let settings = Settings {
+            viewport: (4, 1),
+            ..Settings::default()
+        };
+        let mut animation = Animation::headless(settings);
+        let steps = animation.run(&mut Walk { at: 0, len: 8 });
+        assert_eq!(steps, 7);
+        let frames = animation.frames();
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[0], "@...");
+        assert_eq!(frames[2], "..@.");
+        // scrolled along to keep the dot in view, but no further than the end of the line
+        assert_eq!(frames[3], "..@.");
+        assert_eq!(frames[7], "...@");
+    }
+
+    #[test]
+    fn test_every_and_limit() {
+        let settings = Settings {
+            every: 3,
+            limit: Some(5),
+            ..Settings::default()
+        };
+        let mut animation = Animation::headless(settings);
+        assert_eq!(animation.run(&mut Walk { at: 0, len: 20 }), 5);
+        // the start, step 3, and where the limit stopped it
+        assert_eq!(animation.frames().len(), 3);
+        assert_eq!(animation.frames()[2], ".....@..............");
+    }
+
+    #[test]
+    fn test_stops() {
+        let settings = Settings::default();
+        assert!(settings.stops(&Walk { at: 0, len: 8 }));
+        let tower = crate::day17::animate(">><").unwrap();
+        assert!(!settings.stops(tower.as_ref()));
+        let settings = Settings {
+            limit: Some(100),
+            ..settings
+        };
+        assert!(settings.stops(tower.as_ref()));
+    }
+}
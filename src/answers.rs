@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+
+use crate::error::FileError;
+
+/// Name of the answers file inside the inputs directory. Like the inputs, the answers are
+/// different for everyone, so they live with the inputs rather than in the repo.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+const LINE_FORMAT: &str = "<day> <part> <variant> <answer>";
+
+/// Known-good answers, one per day, part and input variant.
+///
+/// On disk it is one answer per line, e.g. `5 1 input CMZ`. `#` starts a comment line.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    expected: BTreeMap<(u8, u8, String), String>,
+}
+
+/// Puts `answer` on one line, as it is written in the file.
+pub fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut out = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            (c, _) => out.push(c),
+        }
+    }
+    out
+}
+
+fn parse_line(line: &str) -> Option<((u8, u8, String), String)> {
+    let mut fields = line.splitn(4, ' ');
+    let day = fields.next()?.parse().ok()?;
+    let part = fields.next()?.parse().ok()?;
+    let variant = fields.next()?.to_string();
+    let answer = fields.next().filter(|a| !a.is_empty())?;
+    Some(((day, part, variant), unescape(answer)))
+}
+
+impl Answers {
+    pub fn parse(path: &Path, text: &str) -> Result<Self, FileError> {
+        let mut expected = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, answer) = parse_line(line).ok_or_else(|| FileError::Malformed {
+                path: path.to_owned(),
+                line: i + 1,
+                text: line.to_string(),
+                expected: LINE_FORMAT,
+            })?;
+            expected.insert(key, answer);
+        }
+        Ok(Self { expected })
+    }
+
+    /// A file that doesn't exist yet just has no answers in it.
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(path, &text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(FileError::Io {
+                path: path.to_owned(),
+                err,
+            }),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        fs::write(path, self.to_string()).map_err(|err| FileError::Io {
+            path: path.to_owned(),
+            err,
+        })
+    }
+
+    pub fn get(&self, day: u8, part: u8, variant: &str) -> Option<&str> {
+        self.expected
+            .get(&(day, part, variant.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, variant: &str, answer: String) {
+        self.expected
+            .insert((day, part, variant.to_string()), answer);
+    }
+
+    /// Every variant with an answer recorded for `day`.
+    pub fn variants(&self, day: u8) -> Vec<&str> {
+        let mut variants: Vec<&str> = self
+            .expected
+            .keys()
+            .filter(|(d, _, _)| *d == day)
+            .map(|(_, _, variant)| variant.as_str())
+            .collect();
+        variants.sort();
+        variants.dedup();
+        variants
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# {}, with newlines in answers written as \\n", LINE_FORMAT)?;
+        for ((day, part, variant), answer) in &self.expected {
+            writeln!(f, "{} {} {} {}", day, part, variant, escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "# comment\n5 1 input CMZ\n\n10 2 example #.\\n.#\n";
+        let answers = Answers::parse(Path::new("answers.txt"), text).unwrap();
+        assert_eq!(answers.get(5, 1, "input"), Some("CMZ"));
+        assert_eq!(answers.get(10, 2, "example"), Some("#.\n.#"));
+        assert_eq!(answers.get(5, 2, "input"), None);
+        assert_eq!(answers.variants(10), vec!["example"]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.insert(10, 2, "input", "#.\\\n.#".to_string());
+        answers.insert(1, 1, "input", "24000".to_string());
+        answers.insert(1, 1, "example", "240".to_string());
+        let path = Path::new("answers.txt");
+        assert_eq!(Answers::parse(path, &answers.to_string()).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let err = Answers::parse(Path::new("answers.txt"), "1 1 input 3\n1 x input 4\n");
+        assert!(matches!(err, Err(FileError::Malformed { line: 2, .. })));
+        let err = Answers::parse(Path::new("answers.txt"), "1 1 input\n");
+        assert!(matches!(err, Err(FileError::Malformed { line: 1, .. })));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    hint::black_box,
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{Error, FileError},
+    solution::Parser,
+};
+
+/// What gets timed for each day, in order.
+pub const STAGES: [&str; 3] = ["parse", "part1", "part2"];
+
+const LINE_FORMAT: &str = "<day> <stage> <min ns> <median ns> <max ns>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+fn time<T>(iterations: usize, mut f: impl FnMut() -> T) -> Stats {
+    let samples = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+/// Times parsing and each part separately, `iterations` times each. The parts all run on the
+/// same parsed input, so only the first parse can fail. A part with no answer isn't timed, as it
+/// would only be timing how soon it gives up.
+pub fn bench(parse: Parser, input: &str, iterations: usize) -> Result<[Stats; 3], Error> {
+    let solution = parse(input)?;
+    solution.part1()?;
+    solution.part2()?;
+    Ok([
+        time(iterations, || parse(black_box(input))),
+        time(iterations, || solution.part1()),
+        time(iterations, || solution.part2()),
+    ])
+}
+
+/// Earlier timings to compare against, saved one stage per line as
+/// `<day> <stage> <min ns> <median ns> <max ns>`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    stats: BTreeMap<(u8, String), Stats>,
+}
+
+fn parse_line(line: &str) -> Option<((u8, String), Stats)> {
+    let fields: Vec<&str> = line.split(' ').collect();
+    let [day, stage, min, median, max] = fields[..] else {
+        return None;
+    };
+    let nanos = |s: &str| s.parse().ok().map(Duration::from_nanos);
+    Some((
+        (day.parse().ok()?, stage.to_string()),
+        Stats {
+            min: nanos(min)?,
+            median: nanos(median)?,
+            max: nanos(max)?,
+        },
+    ))
+}
+
+impl Baseline {
+    pub fn parse(path: &Path, text: &str) -> Result<Self, FileError> {
+        let mut stats = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, stat) = parse_line(line).ok_or_else(|| FileError::Malformed {
+                path: path.to_owned(),
+                line: i + 1,
+                text: line.to_string(),
+                expected: LINE_FORMAT,
+            })?;
+            stats.insert(key, stat);
+        }
+        Ok(Self { stats })
+    }
+
+    /// A baseline that hasn't been saved yet is empty, so saving can always build on `load`.
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(path, &text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(FileError::Io {
+                path: path.to_owned(),
+                err,
+            }),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        fs::write(path, self.to_string()).map_err(|err| FileError::Io {
+            path: path.to_owned(),
+            err,
+        })
+    }
+
+    pub fn get(&self, day: u8, stage: &str) -> Option<&Stats> {
+        self.stats.get(&(day, stage.to_string()))
+    }
+
+    pub fn insert(&mut self, day: u8, stage: &str, stats: Stats) {
+        self.stats.insert((day, stage.to_string()), stats);
+    }
+
+    /// Takes every timing from `other`, replacing any already here for the same stage.
+    pub fn extend(&mut self, other: Baseline) {
+        self.stats.extend(other.stats);
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# {}", LINE_FORMAT)?;
+        for ((day, stage), stats) in &self.stats {
+            writeln!(
+                f,
+                "{} {} {} {} {}",
+                day,
+                stage,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.max.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub const HEADER: &str = "day stage         min      median         max";
+
+/// One line of the bench table, with how the median moved if there is something to compare to.
+pub fn row(day: u8, stage: &str, stats: &Stats, before: Option<&Stats>) -> String {
+    let mut row = format!(
+        "{:>3} {:5} {:>11.2?} {:>11.2?} {:>11.2?}",
+        day, stage, stats.min, stats.median, stats.max
+    );
+    if let Some(before) = before {
+        row += &format!("  {}", change(stats.median, before.median));
+    }
+    row
+}
+
+/// How much slower or faster `now` is than `before`: `+12.5% (was 1.20ms)`.
+pub fn change(now: Duration, before: Duration) -> String {
+    let change = (now.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("{:+.1}% (was {:.2?})", change, before)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!((stats.min, stats.median, stats.max), (ms(1), ms(3), ms(5)));
+        let stats = Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(stats.median, ms(3));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        let stats = Stats {
+            min: Duration::from_nanos(10),
+            median: Duration::from_nanos(25),
+            max: Duration::from_nanos(1000),
+        };
+        baseline.insert(15, "part1", stats);
+        let path = Path::new("baseline.txt");
+        let loaded = Baseline::parse(path, &baseline.to_string()).unwrap();
+        assert_eq!(loaded.get(15, "part1"), Some(&stats));
+        assert!(matches!(
+            Baseline::parse(path, "15 part1 10 25"),
+            Err(FileError::Malformed { line: 1, .. })
+        ));
+    }
+}
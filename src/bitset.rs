@@ -0,0 +1,168 @@
+//! Small sets of numbers below 64 packed into a single `u64`, for when a `HashSet` would spend
+//! most of its time hashing. Being `Copy` and `Hash` they also make cheap keys for search states.
+
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitSet(u64);
+
+impl BitSet {
+    /// How many numbers a set can hold: `0..CAPACITY`.
+    pub const CAPACITY: usize = 64;
+
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Panics if `n` is too big to fit.
+    pub fn insert(&mut self, n: usize) {
+        assert!(n < Self::CAPACITY, "{} doesn't fit in a bitset", n);
+        self.0 |= 1 << n;
+    }
+
+    pub fn remove(&mut self, n: usize) {
+        if n < Self::CAPACITY {
+            self.0 &= !(1 << n);
+        }
+    }
+
+    /// A copy with `n` added.
+    pub fn with(mut self, n: usize) -> Self {
+        self.insert(n);
+        self
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        n < Self::CAPACITY && self.0 & (1 << n) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// The numbers in the set, lowest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let mut left = self.0;
+        std::iter::from_fn(move || {
+            let n = left.trailing_zeros() as usize;
+            // clear the lowest bit
+            left &= left.checked_sub(1)?;
+            Some(n)
+        })
+    }
+}
+
+impl BitOr for BitSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(&other)
+    }
+}
+
+impl BitAnd for BitSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(&other)
+    }
+}
+
+impl Sub for BitSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(&other)
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<T: IntoIterator<Item = usize>>(numbers: T) -> Self {
+        let mut set = Self::new();
+        for n in numbers {
+            set.insert(n);
+        }
+        set
+    }
+}
+
+/// Like a `HashSet`: `{1, 5, 9}`.
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::rng::forall;
+
+    #[test]
+    fn test_set_algebra() {
+        let a = BitSet::from_iter([0, 3, 63]);
+        let b = BitSet::new().with(3).with(4);
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![0, 3, 63]);
+        assert_eq!(a.len(), 3);
+        assert!(a.contains(63) && !a.contains(64) && !a.contains(1));
+        assert_eq!(a | b, BitSet::from_iter([0, 3, 4, 63]));
+        assert_eq!(a & b, BitSet::from_iter([3]));
+        assert_eq!(a - b, BitSet::from_iter([0, 63]));
+        assert!(!a.is_disjoint(&b) && (a - b).is_disjoint(&b));
+        assert!((a & b).is_subset(&a) && !b.is_subset(&a));
+        assert_eq!(format!("{:?}", b), "{3, 4}");
+        let mut c = a;
+        c.remove(0);
+        c.remove(100);
+        assert_eq!(c, BitSet::from_iter([3, 63]));
+    }
+
+    #[test]
+    fn test_against_btreeset() {
+        forall(200, |rng| {
+            let mut random =
+                || -> BTreeSet<usize> { (0..rng.below(10)).map(|_| rng.below(64)).collect() };
+            let (a_numbers, b_numbers) = (random(), random());
+            let (a, b) = (
+                BitSet::from_iter(a_numbers.iter().copied()),
+                BitSet::from_iter(b_numbers.iter().copied()),
+            );
+            let numbers = |set: BitSet| set.iter().collect::<BTreeSet<_>>();
+            assert_eq!(numbers(a), a_numbers);
+            assert_eq!(a.len(), a_numbers.len());
+            assert_eq!(numbers(a | b), &a_numbers | &b_numbers);
+            assert_eq!(numbers(a & b), &a_numbers & &b_numbers);
+            assert_eq!(numbers(a - b), &a_numbers - &b_numbers);
+            assert_eq!(a.is_subset(&b), a_numbers.is_subset(&b_numbers));
+        });
+    }
+}
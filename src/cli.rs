@@ -0,0 +1,909 @@
+use std::{fmt::Display, path::PathBuf};
+
+use crate::{
+    animate::Settings,
+    generate::{DEFAULT_SEED, DEFAULT_SIZE},
+    input::Source,
+};
+
+pub const USAGE: &str = "usage:
+    aoc-2022 run <day> [--part <1|2>] [--inputs <dir> | --input <path|->]
+                 [--format <text|json>] [--watch]
+    aoc-2022 run --all [--part <1|2>] [--inputs <dir>] [--format <text|json>] [--jobs <n>]
+    aoc-2022 verify [--inputs <dir>] [--answers <path>] [--record]
+    aoc-2022 bench <day>|--all [--iterations <n>] [--inputs <dir> | --input <path|->]
+                   [--size <n>] [--seed <n>] [--save <path>] [--compare <path>]
+    aoc-2022 animate <day> [--inputs <dir> | --input <path|->] [--fps <n>] [--every <n>]
+                     [--size <columns>x<rows>] [--steps <n>] [--paused] [--record <path>]
+    aoc-2022 image <day> [--inputs <dir> | --input <path|->] [--out <path>] [--scale <n>]
+                   [--sequence <dir> [--every <n>] [--steps <n>]]
+    aoc-2022 generate <day> [--size <n>] [--seed <n>]
+    aoc-2022 new <day>
+
+inputs are read from <dir>/day<N>.txt, where <dir> defaults to $AOC_INPUTS or src/data.
+`--input -` reads the input from stdin.
+
+`run --all` runs the days on a thread per core (or `--jobs` threads) and prints a table of the
+answers and timings once they're all done. A day that fails or panics is reported in the table
+without stopping the others.
+
+`run --format json` prints one JSON object per line for each day and part, with the answer, the
+input it came from and how long parsing (`parse_ns`) and the part (`part_ns`) took. A part
+without an answer has an `error` instead, and `null` for whatever wasn't timed.
+
+`run --watch` runs the day on day<N>.txt and every day<N>.<variant>.txt next to it (or just
+`--input`) and then again whenever one of them changes, showing how the answers and timings
+moved since the last time. It keeps going until interrupted.
+
+`verify` checks every day against the answers in <dir>/answers.txt (or `--answers`), one
+`<day> <part> <variant> <answer>` per line. Variant `input` is day<N>.txt, any other variant is
+day<N>.<variant>.txt. `--record` saves answers that weren't known yet.
+
+`bench` times parsing and each part separately, 10 times unless `--iterations` says otherwise.
+`--save` adds the timings to a baseline file and `--compare` shows how they changed since.
+`--size` and `--seed` time a generated input (see `generate`) instead of the real one.
+Baselines only keep timings of the real inputs, so they don't go with `--save` or `--compare`.
+
+`animate` shows a day's simulation (days 9, 14 and 17) at 30 frames a second (`--fps`, 0 for
+as fast as possible), drawing every step (or every `--every`th) in an 80x24 window that follows
+the action. Press enter to pause and again to step, c to carry on, and q to quit. `--steps`
+stops after that many steps. `--record` runs without drawing and saves the frames instead,
+which needs `--steps` for day 17 as its rocks never stop falling.
+
+`image` draws a day's grid (days 8, 12, 14 and 17) as a PGM or PPM picture, day<N>.pgm or
+day<N>.ppm unless `--out` says otherwise, with each cell 4 pixels across (`--scale`).
+`--sequence` saves an animated day's frames as numbered pictures in <dir> instead, which for
+day 17 needs `--steps` to say when to stop.
+
+`generate` prints a made-up input for a day, the same one every time for the same seed (2022
+unless `--seed` says otherwise). `--size` (100 by default) says how big: more lines, elves,
+sensors, valves, jets and so on.
+
+`new` starts a day off: src/day<N>.rs with a `Solution` to fill in, an empty
+src/examples/day<N>.txt to paste the puzzle's example into, the example added to
+src/examples.rs to fill in its answers, and the day added to the `days!` table in src/lib.rs
+so the runner knows about it. Run it from the top of the repository.";
+
+/// How many pixels across each cell of a grid is drawn when not told otherwise.
+pub const DEFAULT_SCALE: usize = 4;
+
+/// How many times `bench` runs each stage when not told otherwise.
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Days {
+    One(u8),
+    All,
+}
+
+/// How `run` prints its answers.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    #[default]
+    Text,
+    /// A line of JSON per answer, see [`crate::json`].
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        days: Days,
+        part: Option<u8>,
+        input: Option<Source>,
+        format: Format,
+        /// How many days `--all` runs at once, one per core if not given.
+        jobs: Option<usize>,
+        /// Run again whenever the input changes, see [`crate::watch`].
+        watch: bool,
+    },
+    Verify {
+        inputs: Option<PathBuf>,
+        answers: Option<PathBuf>,
+        record: bool,
+    },
+    Bench {
+        days: Days,
+        input: Option<Source>,
+        iterations: usize,
+        save: Option<PathBuf>,
+        compare: Option<PathBuf>,
+    },
+    Animate {
+        day: u8,
+        input: Option<Source>,
+        settings: Settings,
+        record: Option<PathBuf>,
+    },
+    Image {
+        day: u8,
+        input: Option<Source>,
+        out: Option<PathBuf>,
+        scale: usize,
+        /// Where to save the frames of the animation, if that's what's wanted. Only the step
+        /// settings count.
+        sequence: Option<PathBuf>,
+        settings: Settings,
+    },
+    Generate {
+        day: u8,
+        size: usize,
+        seed: u64,
+    },
+    New {
+        day: u8,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UsageError {
+    MissingCommand,
+    UnknownCommand(String),
+    UnexpectedArg(String),
+    MissingValue(&'static str),
+    InvalidDay(String),
+    InvalidPart(String),
+    InvalidIterations(String),
+    InvalidValue {
+        flag: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    MissingDay(&'static str),
+    InputWithAll,
+    InputWithGenerated,
+    WatchWithAll,
+    WatchStdin,
+    JobsWithoutAll,
+    /// The flag of the baseline given along with `--size` or `--seed`.
+    BaselineWithGenerated(&'static str),
+    /// `--every` or `--steps` given to `image` with no `--sequence` for them to step through.
+    StepsWithoutSequence(&'static str),
+}
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingCommand => write!(f, "no command given"),
+            Self::UnknownCommand(cmd) => write!(f, "unknown command `{}`", cmd),
+            Self::UnexpectedArg(arg) => write!(f, "unexpected argument `{}`", arg),
+            Self::MissingValue(flag) => write!(f, "`{}` needs a value", flag),
+            Self::InvalidDay(day) => write!(f, "`{}` is not a day, expected 1-25", day),
+            Self::InvalidPart(part) => write!(f, "`{}` is not a part, expected 1 or 2", part),
+            Self::InvalidIterations(n) => {
+                write!(f, "`{}` is not a number of iterations, expected 1 or more", n)
+            }
+            Self::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(f, "`{}` is not valid for `{}`, expected {}", value, flag, expected),
+            Self::MissingDay(cmd @ ("animate" | "image" | "generate" | "new")) => {
+                write!(f, "`{}` needs a day", cmd)
+            }
+            Self::MissingDay(cmd) => write!(f, "`{}` needs a day or `--all`", cmd),
+            Self::InputWithAll => write!(f, "`--input` only works with a single day"),
+            Self::WatchWithAll => write!(f, "`--watch` only works with a single day"),
+            Self::WatchStdin => write!(f, "`--watch` needs input files to watch, not stdin"),
+            Self::JobsWithoutAll => write!(f, "`--jobs` only works with `--all`"),
+            Self::StepsWithoutSequence(flag) => {
+                write!(f, "`{}` only works with `--sequence`", flag)
+            }
+            Self::BaselineWithGenerated(flag) => write!(
+                f,
+                "baselines only keep timings of the real inputs, so there's no `{}` with \
+                 `--size` or `--seed`",
+                flag
+            ),
+            Self::InputWithGenerated => {
+                write!(
+                    f,
+                    "`--size` and `--seed` generate the input, so there's no `--input` with them"
+                )
+            }
+        }
+    }
+}
+
+fn parse_day(arg: &str) -> Result<u8, UsageError> {
+    match arg.trim_start_matches("day").parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(UsageError::InvalidDay(arg.to_string())),
+    }
+}
+
+fn parse_iterations(arg: &str) -> Result<usize, UsageError> {
+    match arg.parse() {
+        Ok(n @ 1..) => Ok(n),
+        _ => Err(UsageError::InvalidIterations(arg.to_string())),
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(
+    flag: &'static str,
+    arg: &str,
+    expected: &'static str,
+    valid: impl Fn(&T) -> bool,
+) -> Result<T, UsageError> {
+    arg.parse().ok().filter(valid).ok_or(UsageError::InvalidValue {
+        flag,
+        value: arg.to_string(),
+        expected,
+    })
+}
+
+fn parse_size(arg: &str) -> Result<(usize, usize), UsageError> {
+    let size = arg.split_once('x').and_then(|(columns, rows)| {
+        Some((columns.parse().ok()?, rows.parse().ok()?))
+    });
+    match size {
+        Some((columns @ 1.., rows @ 1..)) => Ok((columns, rows)),
+        _ => Err(UsageError::InvalidValue {
+            flag: "--size",
+            value: arg.to_string(),
+            expected: "<columns>x<rows>, like 80x24",
+        }),
+    }
+}
+
+fn parse_format(arg: &str) -> Result<Format, UsageError> {
+    match arg {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(UsageError::InvalidValue {
+            flag: "--format",
+            value: arg.to_string(),
+            expected: "text or json",
+        }),
+    }
+}
+
+fn parse_part(arg: &str) -> Result<u8, UsageError> {
+    match arg.parse() {
+        Ok(part @ 1..=2) => Ok(part),
+        _ => Err(UsageError::InvalidPart(arg.to_string())),
+    }
+}
+
+impl Command {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, UsageError> {
+        let mut args = args.into_iter();
+        match args.next().as_deref() {
+            Some("run") => Self::parse_run(args),
+            Some("verify") => Self::parse_verify(args),
+            Some("bench") => Self::parse_bench(args),
+            Some("animate") => Self::parse_animate(args),
+            Some("image") => Self::parse_image(args),
+            Some("generate") => Self::parse_generate(args),
+            Some("new") => Self::parse_new(args),
+            Some("help" | "-h" | "--help") => Ok(Self::Help),
+            Some(cmd) => Err(UsageError::UnknownCommand(cmd.to_string())),
+            None => Err(UsageError::MissingCommand),
+        }
+    }
+
+    fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut days = None;
+        let mut part = None;
+        let mut input = None;
+        let mut format = Format::default();
+        let mut jobs = None;
+        let mut watch = false;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" if days.is_none() => days = Some(Days::All),
+                "--part" | "-p" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--part"))?;
+                    part = Some(parse_part(&val)?);
+                }
+                "--format" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--format"))?;
+                    format = parse_format(&val)?;
+                }
+                "--jobs" | "-j" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--jobs"))?;
+                    jobs = Some(parse_value("--jobs", &val, "1 or more", |&n| n > 0)?);
+                }
+                "--watch" | "-w" => watch = true,
+                "--input" | "-i" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--input"))?;
+                    input = Some(Source::from(val.as_str()));
+                }
+                "--inputs" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--inputs"))?;
+                    input = Some(Source::Dir(val.into()));
+                }
+                day if days.is_none() && !day.starts_with('-') => {
+                    days = Some(Days::One(parse_day(day)?))
+                }
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let days = days.ok_or(UsageError::MissingDay("run"))?;
+        if let (Days::All, Some(Source::File(_) | Source::Stdin)) = (days, &input) {
+            return Err(UsageError::InputWithAll);
+        }
+        if let (Days::One(_), Some(_)) = (days, jobs) {
+            return Err(UsageError::JobsWithoutAll);
+        }
+        match (watch, days, &input) {
+            (true, Days::All, _) => return Err(UsageError::WatchWithAll),
+            (true, _, Some(Source::Stdin)) => return Err(UsageError::WatchStdin),
+            _ => (),
+        }
+        Ok(Self::Run {
+            days,
+            part,
+            input,
+            format,
+            jobs,
+            watch,
+        })
+    }
+
+    fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut days = None;
+        let mut input = None;
+        let mut iterations = DEFAULT_ITERATIONS;
+        let mut save = None;
+        let mut compare = None;
+        let (mut size, mut seed) = (None, None);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" if days.is_none() => days = Some(Days::All),
+                "--iterations" | "-n" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--iterations"))?;
+                    iterations = parse_iterations(&val)?;
+                }
+                "--input" | "-i" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--input"))?;
+                    input = Some(Source::from(val.as_str()));
+                }
+                "--inputs" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--inputs"))?;
+                    input = Some(Source::Dir(val.into()));
+                }
+                "--save" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--save"))?;
+                    save = Some(val.into());
+                }
+                "--compare" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--compare"))?;
+                    compare = Some(val.into());
+                }
+                "--size" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--size"))?;
+                    size = Some(parse_value("--size", &val, "a number", |_| true)?);
+                }
+                "--seed" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--seed"))?;
+                    seed = Some(parse_value("--seed", &val, "a number", |_| true)?);
+                }
+                day if days.is_none() && !day.starts_with('-') => {
+                    days = Some(Days::One(parse_day(day)?))
+                }
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let days = days.ok_or(UsageError::MissingDay("bench"))?;
+        if let (Days::All, Some(Source::File(_) | Source::Stdin)) = (days, &input) {
+            return Err(UsageError::InputWithAll);
+        }
+        if size.is_some() || seed.is_some() {
+            if input.is_some() {
+                return Err(UsageError::InputWithGenerated);
+            }
+            if save.is_some() {
+                return Err(UsageError::BaselineWithGenerated("--save"));
+            }
+            if compare.is_some() {
+                return Err(UsageError::BaselineWithGenerated("--compare"));
+            }
+            input = Some(Source::Generated {
+                size: size.unwrap_or(DEFAULT_SIZE),
+                seed: seed.unwrap_or(DEFAULT_SEED),
+            });
+        }
+        Ok(Self::Bench {
+            days,
+            input,
+            iterations,
+            save,
+            compare,
+        })
+    }
+
+    fn parse_animate<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut day = None;
+        let mut input = None;
+        let mut settings = Settings::default();
+        let mut record = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" | "-i" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--input"))?;
+                    input = Some(Source::from(val.as_str()));
+                }
+                "--inputs" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--inputs"))?;
+                    input = Some(Source::Dir(val.into()));
+                }
+                "--fps" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--fps"))?;
+                    settings.fps = parse_value("--fps", &val, "a number of frames", |_| true)?;
+                }
+                "--every" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--every"))?;
+                    settings.every = parse_value("--every", &val, "1 or more", |&n| n > 0)?;
+                }
+                "--size" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--size"))?;
+                    settings.viewport = parse_size(&val)?;
+                }
+                "--steps" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--steps"))?;
+                    settings.limit = Some(parse_value("--steps", &val, "a number", |_| true)?);
+                }
+                "--paused" => settings.paused = true,
+                "--record" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--record"))?;
+                    record = Some(val.into());
+                }
+                d if day.is_none() && !d.starts_with('-') => day = Some(parse_day(d)?),
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let day = day.ok_or(UsageError::MissingDay("animate"))?;
+        Ok(Self::Animate {
+            day,
+            input,
+            settings,
+            record,
+        })
+    }
+
+    fn parse_image<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut day = None;
+        let mut input = None;
+        let mut out = None;
+        let mut scale = DEFAULT_SCALE;
+        let mut sequence = None;
+        let mut settings = Settings::default();
+        // the last of `--every` and `--steps` given, which only say anything about a sequence
+        let mut stepping = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" | "-i" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--input"))?;
+                    input = Some(Source::from(val.as_str()));
+                }
+                "--inputs" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--inputs"))?;
+                    input = Some(Source::Dir(val.into()));
+                }
+                "--out" | "-o" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--out"))?;
+                    out = Some(val.into());
+                }
+                "--scale" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--scale"))?;
+                    scale = parse_value("--scale", &val, "1 or more", |&n| n > 0)?;
+                }
+                "--sequence" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--sequence"))?;
+                    sequence = Some(val.into());
+                }
+                "--every" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--every"))?;
+                    settings.every = parse_value("--every", &val, "1 or more", |&n| n > 0)?;
+                    stepping = Some("--every");
+                }
+                "--steps" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--steps"))?;
+                    settings.limit = Some(parse_value("--steps", &val, "a number", |_| true)?);
+                    stepping = Some("--steps");
+                }
+                d if day.is_none() && !d.starts_with('-') => day = Some(parse_day(d)?),
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let day = day.ok_or(UsageError::MissingDay("image"))?;
+        if let (Some(flag), None) = (stepping, &sequence) {
+            return Err(UsageError::StepsWithoutSequence(flag));
+        }
+        Ok(Self::Image {
+            day,
+            input,
+            out,
+            scale,
+            sequence,
+            settings,
+        })
+    }
+
+    fn parse_generate<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut day = None;
+        let mut size = DEFAULT_SIZE;
+        let mut seed = DEFAULT_SEED;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--size" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--size"))?;
+                    size = parse_value("--size", &val, "a number", |_| true)?;
+                }
+                "--seed" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--seed"))?;
+                    seed = parse_value("--seed", &val, "a number", |_| true)?;
+                }
+                d if day.is_none() && !d.starts_with('-') => day = Some(parse_day(d)?),
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let day = day.ok_or(UsageError::MissingDay("generate"))?;
+        Ok(Self::Generate { day, size, seed })
+    }
+
+    fn parse_new<I: Iterator<Item = String>>(args: I) -> Result<Self, UsageError> {
+        let mut day = None;
+        for arg in args {
+            match arg.as_str() {
+                d if day.is_none() && !d.starts_with('-') => day = Some(parse_day(d)?),
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        let day = day.ok_or(UsageError::MissingDay("new"))?;
+        Ok(Self::New { day })
+    }
+
+    fn parse_verify<I: Iterator<Item = String>>(mut args: I) -> Result<Self, UsageError> {
+        let mut inputs = None;
+        let mut answers = None;
+        let mut record = false;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--inputs" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--inputs"))?;
+                    inputs = Some(val.into());
+                }
+                "--answers" => {
+                    let val = args.next().ok_or(UsageError::MissingValue("--answers"))?;
+                    answers = Some(val.into());
+                }
+                "--record" => record = true,
+                _ => return Err(UsageError::UnexpectedArg(arg)),
+            }
+        }
+        Ok(Self::Verify {
+            inputs,
+            answers,
+            record,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, UsageError> {
+        Command::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_run_day() {
+        assert_eq!(
+            parse("run 15 --part 2"),
+            Ok(Command::Run {
+                days: Days::One(15),
+                part: Some(2),
+                input: None,
+                format: Format::Text,
+                jobs: None,
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run day3"),
+            Ok(Command::Run {
+                days: Days::One(3),
+                part: None,
+                input: None,
+                format: Format::Text,
+                jobs: None,
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run 12 --watch --input day12.example.txt"),
+            Ok(Command::Run {
+                days: Days::One(12),
+                part: None,
+                input: Some(Source::File("day12.example.txt".into())),
+                format: Format::Text,
+                jobs: None,
+                watch: true,
+            })
+        );
+        assert_eq!(parse("run --all --watch"), Err(UsageError::WatchWithAll));
+        assert_eq!(parse("run 3 -w --input -"), Err(UsageError::WatchStdin));
+        assert_eq!(parse("run 3 --jobs 2"), Err(UsageError::JobsWithoutAll));
+    }
+
+    #[test]
+    fn test_parse_run_all() {
+        assert_eq!(
+            parse("run --all"),
+            Ok(Command::Run {
+                days: Days::All,
+                part: None,
+                input: None,
+                format: Format::Text,
+                jobs: None,
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run --all --format json -j 3"),
+            Ok(Command::Run {
+                days: Days::All,
+                part: None,
+                input: None,
+                format: Format::Json,
+                jobs: Some(3),
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run 1 --format yaml"),
+            Err(UsageError::InvalidValue {
+                flag: "--format",
+                value: "yaml".into(),
+                expected: "text or json",
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_inputs() {
+        assert_eq!(
+            parse("run 5 --input -"),
+            Ok(Command::Run {
+                days: Days::One(5),
+                part: None,
+                input: Some(Source::Stdin),
+                format: Format::Text,
+                jobs: None,
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run --all --inputs elsewhere"),
+            Ok(Command::Run {
+                days: Days::All,
+                part: None,
+                input: Some(Source::Dir("elsewhere".into())),
+                format: Format::Text,
+                jobs: None,
+                watch: false,
+            })
+        );
+        assert_eq!(
+            parse("run --all --input day5.txt"),
+            Err(UsageError::InputWithAll)
+        );
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(
+            parse("verify"),
+            Ok(Command::Verify {
+                inputs: None,
+                answers: None,
+                record: false,
+            })
+        );
+        assert_eq!(
+            parse("verify --record --inputs in --answers in/known.txt"),
+            Ok(Command::Verify {
+                inputs: Some("in".into()),
+                answers: Some("in/known.txt".into()),
+                record: true,
+            })
+        );
+        assert_eq!(
+            parse("verify 3"),
+            Err(UsageError::UnexpectedArg("3".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse("bench 11"),
+            Ok(Command::Bench {
+                days: Days::One(11),
+                input: None,
+                iterations: DEFAULT_ITERATIONS,
+                save: None,
+                compare: None,
+            })
+        );
+        assert_eq!(
+            parse("bench --all -n 3 --save base.txt --compare base.txt"),
+            Ok(Command::Bench {
+                days: Days::All,
+                input: None,
+                iterations: 3,
+                save: Some("base.txt".into()),
+                compare: Some("base.txt".into()),
+            })
+        );
+        assert_eq!(
+            parse("bench --all --size 5000 -n 1"),
+            Ok(Command::Bench {
+                days: Days::All,
+                input: Some(Source::Generated {
+                    size: 5000,
+                    seed: DEFAULT_SEED,
+                }),
+                iterations: 1,
+                save: None,
+                compare: None,
+            })
+        );
+        assert_eq!(
+            parse("bench 3 --seed 1 --inputs elsewhere"),
+            Err(UsageError::InputWithGenerated)
+        );
+        assert_eq!(
+            parse("bench --all --size 5000 --save base.txt"),
+            Err(UsageError::BaselineWithGenerated("--save"))
+        );
+        assert_eq!(
+            parse("bench 15 --compare base.txt --seed 2"),
+            Err(UsageError::BaselineWithGenerated("--compare"))
+        );
+        assert_eq!(
+            parse("bench 1 --iterations 0"),
+            Err(UsageError::InvalidIterations("0".into()))
+        );
+        assert_eq!(parse("bench"), Err(UsageError::MissingDay("bench")));
+    }
+
+    #[test]
+    fn test_parse_animate() {
+        assert_eq!(
+            parse("animate 14"),
+            Ok(Command::Animate {
+                day: 14,
+                input: None,
+                settings: Settings::default(),
+                record: None,
+            })
+        );
+        assert_eq!(
+            parse("animate 17 --fps 0 --every 5 --size 40x10 --steps 100 --paused --record out"),
+            Ok(Command::Animate {
+                day: 17,
+                input: None,
+                settings: Settings {
+                    fps: 0,
+                    every: 5,
+                    viewport: (40, 10),
+                    limit: Some(100),
+                    paused: true,
+                },
+                record: Some("out".into()),
+            })
+        );
+        assert_eq!(
+            parse("animate 9 --size 40"),
+            Err(UsageError::InvalidValue {
+                flag: "--size",
+                value: "40".into(),
+                expected: "<columns>x<rows>, like 80x24",
+            })
+        );
+        assert_eq!(parse("animate"), Err(UsageError::MissingDay("animate")));
+    }
+
+    #[test]
+    fn test_parse_image() {
+        assert_eq!(
+            parse("image 8 -o trees.pgm --scale 2"),
+            Ok(Command::Image {
+                day: 8,
+                input: None,
+                out: Some("trees.pgm".into()),
+                scale: 2,
+                sequence: None,
+                settings: Settings::default(),
+            })
+        );
+        assert_eq!(
+            parse("image 14 --sequence frames --every 10 --steps 500"),
+            Ok(Command::Image {
+                day: 14,
+                input: None,
+                out: None,
+                scale: DEFAULT_SCALE,
+                sequence: Some("frames".into()),
+                settings: Settings {
+                    every: 10,
+                    limit: Some(500),
+                    ..Settings::default()
+                },
+            })
+        );
+        assert!(matches!(
+            parse("image 12 --scale 0"),
+            Err(UsageError::InvalidValue { flag: "--scale", .. })
+        ));
+        assert_eq!(
+            parse("image 14 --steps 500"),
+            Err(UsageError::StepsWithoutSequence("--steps"))
+        );
+        assert_eq!(
+            parse("image 17 --every 2 -o tower.ppm"),
+            Err(UsageError::StepsWithoutSequence("--every"))
+        );
+        assert_eq!(parse("image"), Err(UsageError::MissingDay("image")));
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        assert_eq!(
+            parse("generate 15"),
+            Ok(Command::Generate {
+                day: 15,
+                size: DEFAULT_SIZE,
+                seed: DEFAULT_SEED,
+            })
+        );
+        assert_eq!(
+            parse("generate 16 --seed 7 --size 40"),
+            Ok(Command::Generate {
+                day: 16,
+                size: 40,
+                seed: 7,
+            })
+        );
+        assert!(matches!(
+            parse("generate 5 --size lots"),
+            Err(UsageError::InvalidValue { flag: "--size", .. })
+        ));
+        assert_eq!(parse("generate"), Err(UsageError::MissingDay("generate")));
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(parse("new day18"), Ok(Command::New { day: 18 }));
+        assert_eq!(parse("new"), Err(UsageError::MissingDay("new")));
+        assert_eq!(
+            parse("new 18 19"),
+            Err(UsageError::UnexpectedArg("19".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err(UsageError::MissingCommand));
+        assert_eq!(parse("run"), Err(UsageError::MissingDay("run")));
+        assert_eq!(parse("run 26"), Err(UsageError::InvalidDay("26".into())));
+        assert_eq!(
+            parse("run 1 --part 3"),
+            Err(UsageError::InvalidPart("3".into()))
+        );
+        assert_eq!(
+            parse("run 1 --part"),
+            Err(UsageError::MissingValue("--part"))
+        );
+        assert_eq!(parse("run 1 2"), Err(UsageError::UnexpectedArg("2".into())));
+        assert_eq!(
+            parse("jump 1"),
+            Err(UsageError::UnknownCommand("jump".into()))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Simulations that run for far more steps than can actually be run, like a trillion rocks falling
+//! or ten thousand rounds of monkeys throwing things, usually end up going round in circles. Once
+//! one has been found, whatever the simulation is counting can be worked out for any number of
+//! steps from the steps already taken.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Where a simulation started repeating itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// How many steps it took to get into the loop.
+    pub start: usize,
+    /// How many steps it takes to go round it once.
+    pub period: usize,
+}
+
+/// Steps `state` along until `key` says it's somewhere it's been before, giving up after `limit`
+/// steps. The key has to capture everything that decides what happens next, and nothing that
+/// only counts up (like a height) or it will never repeat. `state` is left where the cycle was
+/// spotted, `start + period` steps in.
+pub fn find<S, K: Hash + Eq>(
+    state: &mut S,
+    mut key: impl FnMut(&S) -> K,
+    mut step: impl FnMut(&mut S),
+    limit: usize,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    for steps in 0..=limit {
+        match seen.entry(key(state)) {
+            Entry::Occupied(first) => {
+                return Some(Cycle {
+                    start: *first.get(),
+                    period: steps - first.get(),
+                })
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(steps);
+            }
+        }
+        if steps < limit {
+            step(state);
+        }
+    }
+    None
+}
+
+impl Cycle {
+    /// The value of something that goes up by the same amount every time round the cycle after
+    /// `n` steps, from `at`, its value after any number of steps up to `start + period`.
+    pub fn extrapolate(&self, n: usize, at: impl Fn(usize) -> usize) -> usize {
+        if n <= self.start + self.period {
+            return at(n);
+        }
+        let (cycles, offset) = (
+            (n - self.start) / self.period,
+            (n - self.start) % self.period,
+        );
+        at(self.start + offset) + cycles * (at(self.start + self.period) - at(self.start))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_and_extrapolate() {
+        // 3, 10, 31, 12, 37, 30, 9, 28, 3, ... in steps of x * 3 + 1 mod 41, counting the
+        // total as it goes
+        let step = |(x, total): &mut (u64, usize)| {
+            *x = (*x * 3 + 1) % 41;
+            *total += *x as usize;
+        };
+        let mut state = (3, 0);
+        let mut totals = vec![0];
+        let cycle = find(
+            &mut state,
+            |&(x, _)| x,
+            |state| {
+                step(state);
+                totals.push(state.1);
+            },
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                period: 8
+            }
+        );
+        let mut brute = (3, 0);
+        for n in 0..100 {
+            assert_eq!(cycle.extrapolate(n, |i| totals[i]), brute.1, "after {}", n);
+            step(&mut brute);
+        }
+
+        // a lead-in before the loop, and a limit too short to get round it
+        let lead_in = |&n: &usize| match n < 5 {
+            true => n,
+            false => 5 + (n - 5) % 3,
+        };
+        let cycle = find(&mut 0, lead_in, |n| *n += 1, 100);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 5,
+                period: 3
+            })
+        );
+        assert_eq!(find(&mut 0, |&n: &usize| n, |n| *n += 1, 10), None);
+    }
+}
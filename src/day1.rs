@@ -1,8 +1,16 @@
-use std::num::ParseIntError;
 use std::str::Lines;
 
+use crate::{
+    error::{Error, ParseError},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 1;
+
 trait Elves<'a> {
-    fn elves(&mut self) -> Result<Vec<Vec<u32>>, ParseIntError>
+    /// On failure, gives back the line that wasn't a number.
+    fn elves(&mut self) -> Result<Vec<Vec<u32>>, &'a str>
     where
         Self: Iterator<Item = &'a str>,
     {
@@ -14,7 +22,10 @@ trait Elves<'a> {
                 cur_elf = vec![];
                 continue;
             }
-            cur_elf.push(line.parse()?);
+            cur_elf.push(line.parse().map_err(|_| line)?);
+        }
+        if !cur_elf.is_empty() {
+            acc.push(cur_elf);
         }
         Ok(acc)
     }
@@ -22,17 +33,43 @@ trait Elves<'a> {
 
 impl<'a> Elves<'a> for Lines<'a> {}
 
-pub fn main() {
-    let binding = include_str!("data/day1.txt").lines().elves().unwrap();
-    let mut elves = binding.iter().collect::<Vec<_>>();
-    elves.sort_by_key(|v| v.iter().sum::<u32>());
-    println!(
-        "Calories: {:?}",
-        elves
+/// Total calories carried by each elf, most first.
+pub struct Calories {
+    pub totals: Vec<u32>,
+}
+
+impl Solution for Calories {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut totals: Vec<u32> = input
+            .lines()
+            .elves()
+            .map_err(|line| ParseError::new(DAY, input, line, "expected a number of calories"))?
             .iter()
-            .rev()
-            .take(3)
-            .map(|v| v.iter().sum::<u32>())
-            .sum::<u32>()
-    );
+            .map(|v| v.iter().sum())
+            .collect();
+        totals.sort_by(|a, b| b.cmp(a));
+        Ok(Self { totals })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let most = self.totals.first().ok_or(Error::NoAnswer("no elves"))?;
+        Ok((*most).into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Ok(self.totals.iter().take(3).sum::<u32>().into())
+    }
+}
+
+/// `size` elves, each carrying a few snacks.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let elves: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let snacks: Vec<String> = (0..1 + rng.below(6))
+                .map(|_| (1000 + rng.below(59000)).to_string())
+                .collect();
+            snacks.join("\n")
+        })
+        .collect();
+    elves.join("\n\n") + "\n"
 }
@@ -1,21 +1,34 @@
+use crate::{
+    error::{Error, ParseError, Unexpected},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 10;
+
 #[derive(Debug)]
-enum Op {
+pub enum Op {
     Noop,
     Addx(i32),
 }
 
-impl From<&str> for Op {
-    fn from(line: &str) -> Self {
+impl<'a> TryFrom<&'a str> for Op {
+    type Error = Unexpected<'a>;
+
+    fn try_from(line: &'a str) -> Result<Self, Self::Error> {
         match line.split(" ").collect::<Vec<&str>>()[..] {
-            ["noop"] => Self::Noop,
-            ["addx", val] => Self::Addx(val.parse().unwrap()),
-            _ => unreachable!(),
+            ["noop"] => Ok(Self::Noop),
+            ["addx", val] => val
+                .parse()
+                .map(Self::Addx)
+                .map_err(|_| Unexpected::new(val, "a number")),
+            _ => Err(Unexpected::new(line, "`noop` or `addx <number>`")),
         }
     }
 }
 
 impl Op {
-    fn cycles(&self) -> usize {
+    pub fn cycles(&self) -> usize {
         match self {
             Self::Noop => 1,
             Self::Addx(_) => 2,
@@ -24,52 +37,104 @@ impl Op {
 }
 
 #[derive(Debug)]
-struct Program {
-    ops: Vec<Op>,
-}
-
-impl From<&str> for Program {
-    fn from(prog: &str) -> Self {
-        Self {
-            ops: prog.lines().map(|l| l.into()).collect(),
-        }
-    }
+pub struct Program {
+    pub ops: Vec<Op>,
 }
 
 impl Program {
-    fn run(&mut self) {
-        let mut ops = self.ops.iter();
-        let mut cur_instr = ops.next().unwrap();
-        let mut waiting = cur_instr.cycles();
+    /// The value of the X register during each cycle, starting from the first.
+    pub fn x_values(&self) -> Vec<i32> {
         let mut x = 1;
-        for clock in 0.. {
-            waiting -= 1;
-            if clock != 0 && clock % 40 == 0 {
-                print!("\n");
-            }
-            if (((clock % 40) - x) as i32).abs() <= 1 {
-                print!("#");
-            } else {
-                print!(".");
+        let mut values = Vec::new();
+        for op in &self.ops {
+            for _ in 0..op.cycles() {
+                values.push(x);
             }
-            if waiting != 0 {
-                continue;
+            if let Op::Addx(i) = op {
+                x += i;
             }
-            match cur_instr {
-                Op::Noop => (),
-                Op::Addx(i) => x += i,
+        }
+        values
+    }
+}
+
+impl Solution for Program {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let ops = input
+            .lines()
+            .map(|l| Op::try_from(l).map_err(|e| ParseError::unexpected(DAY, input, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ops })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let strength: i32 = self
+            .x_values()
+            .iter()
+            .enumerate()
+            .skip(19)
+            .step_by(40)
+            // cycles 20, 60, ..., 220, however long the program runs on for
+            .take(6)
+            .map(|(i, x)| (i as i32 + 1) * x)
+            .sum();
+        Ok(strength.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let screen = self
+            .x_values()
+            .chunks(40)
+            // the screen is six rows, anything after is off the bottom
+            .take(6)
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(pos, x)| if (pos as i32 - x).abs() <= 1 { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(screen.into())
+    }
+}
+
+/// `size` instructions, or however many it takes to draw the whole screen if that's more.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    let (mut cycles, mut count) = (0, 0);
+    while count < size || cycles < 240 {
+        match rng.one_in(3) {
+            true => {
+                out += "noop\n";
+                cycles += 1;
             }
-            if let Some(o) = ops.next() {
-                cur_instr = o;
-            } else {
-                break;
+            false => {
+                out += &format!("addx {}\n", rng.range(-20..=20));
+                cycles += 2;
             }
-            waiting = cur_instr.cycles();
         }
+        count += 1;
     }
+    out
 }
 
-pub fn main() {
-    let mut prog: Program = include_str!("data/day10.txt").into();
-    prog.run();
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_runs_past_the_screen() {
+        let program = Program::parse(&"noop\n".repeat(300)).unwrap();
+        // x stays 1, so each strength is just its cycle
+        let strength = 20 + 60 + 100 + 140 + 180 + 220;
+        assert_eq!(program.part1().unwrap(), Answer::Num(strength));
+        let Answer::Text(screen) = program.part2().unwrap() else {
+            panic!("expected a picture");
+        };
+        assert_eq!(screen.lines().count(), 6);
+        // and the sprite covers the first three pixels of every row
+        let row = format!("###{}", ".".repeat(37));
+        assert!(screen.lines().all(|line| line == row));
+    }
 }
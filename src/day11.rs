@@ -2,60 +2,70 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{digit1, newline},
-    combinator::opt,
+    combinator::{map, opt, value},
     multi::many1,
     sequence::{delimited, preceded, terminated, tuple},
     IResult,
 };
 
-#[derive(Debug, Clone)]
-struct Monkey {
-    items: Vec<u128>,
-    inspected: usize,
-    operation: Operation,
-    test_div_by: u128,
-    throw_if_true: usize,
-    throw_if_false: usize,
+use crate::{
+    cycle,
+    error::{Error, ParseError},
+    parsers::{self, comma_list, unsigned},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 11;
+
+const TOO_WORRYING: Error = Error::NoAnswer("a worry level gets too big to hold");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
+    pub items: Vec<u128>,
+    pub inspected: usize,
+    pub operation: Operation,
+    pub test_div_by: u128,
+    pub throw_if_true: usize,
+    pub throw_if_false: usize,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum OptVal {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptVal {
     Val(u128),
     Old,
 }
 
 impl OptVal {
     fn from_str(input: &str) -> IResult<&str, OptVal> {
-        let (input, found) = alt((digit1, tag("old")))(input)?;
-        Ok((
-            input,
-            match found {
-                "old" => OptVal::Old,
-                val => OptVal::Val(val.parse().unwrap()),
-            },
-        ))
+        alt((
+            map(unsigned, OptVal::Val),
+            value(OptVal::Old, tag("old")),
+        ))(input)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum MulOrAdd {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MulOrAdd {
     Mul,
     Add,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Operation {
-    mora: MulOrAdd,
-    a: OptVal,
-    b: OptVal,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub mora: MulOrAdd,
+    pub a: OptVal,
+    pub b: OptVal,
 }
 
 impl Operation {
-    fn exec(&self, old: u128) -> u128 {
+    /// The new worry level, if it isn't too big to hold.
+    pub fn exec(&self, old: u128) -> Option<u128> {
         let e = if let MulOrAdd::Mul = self.mora {
-            |a, b| a * b
+            u128::checked_mul
         } else {
-            |a, b| a + b
+            u128::checked_add
         };
         match (&self.a, &self.b) {
             (&OptVal::Old, &OptVal::Old) => e(old, old),
@@ -94,22 +104,32 @@ fn parse_operation(input: &str) -> IResult<&str, Operation> {
 }
 
 impl Monkey {
-    fn turn(&mut self, remainder_mul: u128) -> Vec<(usize, u128)> {
+    /// Inspects and throws every item. Worry is divided by `relief` after inspecting and, if
+    /// given, kept modulo `remainder_mul` so it stays small without changing any test. `None` if
+    /// an item gets too worrying to hold.
+    pub fn turn(
+        &mut self,
+        relief: u128,
+        remainder_mul: Option<u128>,
+    ) -> Option<Vec<(usize, u128)>> {
         let item_iter = self.items.iter();
         let mut throws = Vec::new();
         for &item in item_iter {
             self.inspected += 1;
-            let new_item = self.operation.exec(item) % remainder_mul;
-            if new_item % self.test_div_by == 0 {
+            let mut new_item = self.operation.exec(item)? / relief;
+            if let Some(remainder_mul) = remainder_mul {
+                new_item %= remainder_mul;
+            }
+            if new_item.is_multiple_of(self.test_div_by) {
                 throws.push((self.throw_if_true, new_item));
             } else {
                 throws.push((self.throw_if_false, new_item));
             }
         }
-        throws
+        Some(throws)
     }
 
-    fn catch(&mut self, item: u128) {
+    pub fn catch(&mut self, item: u128) {
         self.items.push(item);
     }
 
@@ -119,51 +139,362 @@ impl Monkey {
         let (input, elems) = terminated(
             preceded(
                 tag("  Starting items: "),
-                many1(terminated(digit1, opt(tag(", ")))),
+                comma_list(unsigned),
             ),
             newline,
         )(input)?;
         let (input, operation) = terminated(parse_operation, newline)(input)?;
-        let (input, test_div_by) =
-            terminated(preceded(tag("  Test: divisible by "), digit1), newline)(input)?;
+        let (input, test_div_by) = terminated(
+            preceded(tag("  Test: divisible by "), unsigned),
+            newline,
+        )(input)?;
         let (input, throw_if_true) = terminated(
-            preceded(tag("    If true: throw to monkey "), digit1),
+            preceded(tag("    If true: throw to monkey "), unsigned),
             newline,
         )(input)?;
         let (input, throw_if_false) = terminated(
-            preceded(tag("    If false: throw to monkey "), digit1),
+            preceded(tag("    If false: throw to monkey "), unsigned),
             newline,
         )(input)?;
         Ok((
             input,
             Monkey {
-                items: elems.iter().map(|e| e.parse().unwrap()).collect(),
+                items: elems,
                 inspected: 0,
                 operation,
-                test_div_by: test_div_by.parse().unwrap(),
-                throw_if_true: throw_if_true.parse().unwrap(),
-                throw_if_false: throw_if_false.parse().unwrap(),
+                test_div_by,
+                throw_if_true,
+                throw_if_false,
             },
         ))
     }
 }
 
-pub fn main() {
-    let (input, mut monks) =
-        many1(terminated(Monkey::from_str, opt(newline)))(include_str!("data/day11.txt")).unwrap();
-    assert_eq!("", input);
-    let remainder_mul = monks.iter().map(|m| m.test_div_by).fold(1, |acc, x| acc * x);
-    for _round in 0..10000 {
-        for monkidx in 0..monks.len() {
-            let throws = monks[monkidx].turn(remainder_mul);
-            for (t, item) in throws {
-                monks[t].catch(item);
+pub struct Monkeys(pub Vec<Monkey>);
+
+impl Monkeys {
+    /// The two busiest monkeys' inspection counts multiplied, after `rounds` rounds, if no worry
+    /// level gets too big to hold before then.
+    pub fn monkey_business(
+        &self,
+        rounds: usize,
+        relief: u128,
+        remainder_mul: Option<u128>,
+    ) -> Option<usize> {
+        let mut monks = self.0.clone();
+        for _round in 0..rounds {
+            for monkidx in 0..monks.len() {
+                let throws = monks[monkidx].turn(relief, remainder_mul)?;
+                for (t, item) in throws {
+                    monks[t].catch(item);
+                }
+                monks[monkidx].items.clear();
             }
-            monks[monkidx].items.clear();
         }
+        Some(busiest_two(monks.iter().map(|m| m.inspected).collect()))
+    }
+
+    /// The same as [`Monkeys::monkey_business`] with no relief, so worry is kept modulo
+    /// `remainder_mul`. Then each item goes round the monkeys on its own regardless of the others,
+    /// and sooner or later ends a round where it's ended one before with the same worry, so it
+    /// only has to be followed until then.
+    pub fn monkey_business_by_item(&self, rounds: usize, remainder_mul: u128) -> Option<usize> {
+        let mut inspected = vec![0; self.0.len()];
+        for (holder, monkey) in self.0.iter().enumerate() {
+            for &worry in &monkey.items {
+                // how many times each monkey has inspected the item after each round
+                let mut history = vec![vec![0; self.0.len()]];
+                let mut overflowed = false;
+                let cycle = cycle::find(
+                    &mut (holder, worry % remainder_mul),
+                    |&item| item,
+                    |item| {
+                        let mut counts = history.last().unwrap().clone();
+                        match self.round(*item, remainder_mul, &mut counts) {
+                            Some(next) => *item = next,
+                            None => overflowed = true,
+                        }
+                        history.push(counts);
+                    },
+                    rounds,
+                );
+                if overflowed {
+                    return None;
+                }
+                for (monkey, inspected) in inspected.iter_mut().enumerate() {
+                    *inspected += match cycle {
+                        Some(cycle) => cycle.extrapolate(rounds, |round| history[round][monkey]),
+                        None => history[rounds][monkey],
+                    };
+                }
+            }
+        }
+        Some(busiest_two(inspected))
+    }
+
+    /// Where an item held by `holder` with `worry` ends up after a round, counting who inspects
+    /// it. It carries on being thrown until it lands with a monkey that's already had its turn.
+    /// `None` if it gets too worrying to hold on the way.
+    fn round(
+        &self,
+        (mut holder, mut worry): (usize, u128),
+        remainder_mul: u128,
+        counts: &mut [usize],
+    ) -> Option<(usize, u128)> {
+        loop {
+            let monkey = &self.0[holder];
+            counts[holder] += 1;
+            worry = monkey.operation.exec(worry)? % remainder_mul;
+            let to = match worry.is_multiple_of(monkey.test_div_by) {
+                true => monkey.throw_if_true,
+                false => monkey.throw_if_false,
+            };
+            if to <= holder {
+                return Some((to, worry));
+            }
+            holder = to;
+        }
+    }
+
+    /// Finds what the parser can't see on its own: throws to monkeys that aren't there, and tests
+    /// that can't be kept to a remainder, by dividing by 0 or by more than a worry can hold once
+    /// they're all multiplied together. `input` is what the monkeys were parsed from.
+    fn check(&self, input: &str) -> Result<(), ParseError> {
+        // each number to point at is the last thing on its line
+        let tests = input
+            .lines()
+            .filter(|line| line.starts_with("  Test: "))
+            .filter_map(|line| line.rsplit(' ').next());
+        let mut product: u128 = 1;
+        for (monkey, test) in self.0.iter().zip(tests) {
+            if monkey.test_div_by == 0 {
+                let expected = "expected a divisor above 0";
+                return Err(ParseError::new(DAY, input, test, expected));
+            }
+            product = product.checked_mul(monkey.test_div_by).ok_or_else(|| {
+                ParseError::new(DAY, input, test, "the divisors multiply to too much")
+            })?;
+        }
+        let throws = input
+            .lines()
+            .filter(|line| line.starts_with("    If "))
+            .filter_map(|line| line.rsplit(' ').next());
+        let targets = self
+            .0
+            .iter()
+            .flat_map(|monkey| [monkey.throw_if_true, monkey.throw_if_false]);
+        for (target, throw) in targets.zip(throws) {
+            if target >= self.0.len() {
+                let expected = format!("expected a monkey from 0 to {}", self.0.len() - 1);
+                return Err(ParseError::new(DAY, input, throw, expected));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn busiest_two(mut inspected: Vec<usize>) -> usize {
+    inspected.sort();
+    inspected.iter().rev().take(2).product()
+}
+
+impl Solution for Monkeys {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let monks = parsers::all(
+            DAY,
+            input,
+            many1(terminated(Monkey::from_str, opt(newline))),
+        )?;
+        let monkeys = Self(monks);
+        monkeys.check(input)?;
+        Ok(monkeys)
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let business = self.monkey_business(20, 3, None).ok_or(TOO_WORRYING)?;
+        Ok(business.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let remainder_mul = self.0.iter().map(|m| m.test_div_by).product();
+        let business = self
+            .monkey_business_by_item(10000, remainder_mul)
+            .ok_or(TOO_WORRYING)?;
+        Ok(business.into())
+    }
+}
+
+impl Reference for Monkeys {
+    /// Part 1 only ever was simulated the whole way.
+    fn reference_part1(&self) -> Option<Answer> {
+        None
+    }
+
+    /// Plays out every round with all the monkeys at once.
+    fn reference_part2(&self) -> Option<Answer> {
+        let remainder_mul = self.0.iter().map(|m| m.test_div_by).product();
+        Some(self.monkey_business(10000, 1, Some(remainder_mul))?.into())
+    }
+}
+
+/// Eight monkeys holding `size` items between them. Monkeys only throw to earlier monkeys (bar
+/// the first), so no item is worried about more than twice a round and the worry levels of part
+/// 1 can't overflow.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const PRIMES: [usize; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+    let mut items = vec![Vec::new(); PRIMES.len()];
+    for i in 0..size.max(PRIMES.len()) {
+        // everyone gets at least one
+        let monkey = match i < PRIMES.len() {
+            true => i,
+            false => rng.below(PRIMES.len()),
+        };
+        items[monkey].push((1 + rng.below(99)).to_string());
+    }
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let mut out = Vec::new();
+    for (i, items) in items.iter().enumerate() {
+        let mut target = || match i {
+            0 => 1 + rng.below(PRIMES.len() - 1),
+            i => rng.below(i),
+        };
+        let (if_true, if_false) = (target(), target());
+        let operation = match rng.one_in(2) {
+            true => format!("old * {}", 2 + rng.below(18)),
+            false => format!("old + {}", 1 + rng.below(9)),
+        };
+        out.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            i,
+            items.join(", "),
+            operation,
+            primes[i],
+            if_true,
+            if_false
+        ));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{check_parser, whole, Rng};
+
+    fn operand(rng: &mut Rng) -> OptVal {
+        match rng.one_in(2) {
+            true => OptVal::Old,
+            false => OptVal::Val(rng.below(100) as u128),
+        }
+    }
+
+    /// How `monkey` is written down, as the `number`th monkey.
+    fn text(monkey: &Monkey, number: usize) -> String {
+        let operand = |val: &OptVal| match val {
+            OptVal::Old => "old".to_string(),
+            OptVal::Val(val) => val.to_string(),
+        };
+        let sign = match monkey.operation.mora {
+            MulOrAdd::Mul => '*',
+            MulOrAdd::Add => '+',
+        };
+        let items: Vec<_> = monkey.items.iter().map(u128::to_string).collect();
+        format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {} {} {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            number,
+            items.join(", "),
+            operand(&monkey.operation.a),
+            sign,
+            operand(&monkey.operation.b),
+            monkey.test_div_by,
+            monkey.throw_if_true,
+            monkey.throw_if_false
+        )
+    }
+
+    #[test]
+    fn test_impossible_monkeys() {
+        let monkeys = |div_by: &str, to: &str| {
+            format!(
+                "Monkey 0:\n  Starting items: 79\n  Operation: new = old * 19\n  Test: divisible by {}\n    If true: throw to monkey 1\n    If false: throw to monkey {}\n",
+                div_by, to
+            )
+        };
+        let error = |input: &str| {
+            let e = <Monkeys as Solution>::parse(input).map(|_| ()).unwrap_err();
+            (e.line, e.column, e.message)
+        };
+        assert_eq!(
+            error(&monkeys("0", "0")),
+            (4, 22, "expected a divisor above 0".to_string())
+        );
+        let too_much = u128::MAX.to_string();
+        let input = monkeys(&too_much, "0") + "\n" + &monkeys(&too_much, "0");
+        assert_eq!(
+            error(&input),
+            (11, 22, "the divisors multiply to too much".to_string())
+        );
+        assert_eq!(
+            error(&monkeys("23", "0")),
+            (5, 30, "expected a monkey from 0 to 0".to_string())
+        );
+        let input = monkeys("23", "0") + "\n" + &monkeys("19", "2");
+        assert_eq!(
+            error(&input),
+            (13, 31, "expected a monkey from 0 to 1".to_string())
+        );
+        let input = monkeys("23", "0") + "\n" + &monkeys("19", "1");
+        assert!(<Monkeys as Solution>::parse(&input).is_ok());
+    }
+
+    #[test]
+    fn test_too_worrying() {
+        let monkey = |throw_to| Monkey {
+            items: vec![u64::MAX as u128 - 1],
+            inspected: 0,
+            operation: Operation {
+                mora: MulOrAdd::Mul,
+                a: OptVal::Old,
+                b: OptVal::Old,
+            },
+            // so that keeping worry modulo the divisors doesn't keep it small enough either
+            test_div_by: u64::MAX as u128,
+            throw_if_true: throw_to,
+            throw_if_false: throw_to,
+        };
+        let input = text(&monkey(1), 0) + "\n" + &text(&monkey(0), 1);
+        let monkeys = <Monkeys as Solution>::parse(&input).unwrap();
+        let too_worrying = "a worry level gets too big to hold";
+        assert_eq!(monkeys.part1().unwrap_err().to_string(), too_worrying);
+        assert_eq!(monkeys.part2().unwrap_err().to_string(), too_worrying);
+    }
+
+    #[test]
+    fn test_monkey_round_trip() {
+        check_parser(
+            200,
+            "Monkey 0123456789:\n",
+            |rng| {
+                let items: Vec<u128> = (0..1 + rng.below(6))
+                    .map(|_| rng.below(100) as u128)
+                    .collect();
+                let (a, b) = (operand(rng), operand(rng));
+                let mora = *rng.pick(&[MulOrAdd::Mul, MulOrAdd::Add]);
+                let monkey = Monkey {
+                    items,
+                    inspected: 0,
+                    operation: Operation { mora, a, b },
+                    test_div_by: 1 + rng.below(30) as u128,
+                    throw_if_true: rng.below(8),
+                    throw_if_false: rng.below(8),
+                };
+                // the number isn't kept, as the monkeys are in order anyway
+                let text = text(&monkey, rng.below(8));
+                (monkey, text)
+            },
+            |monkey| text(monkey, 0),
+            |text| whole(Monkey::from_str(text)),
+        );
     }
-    println!("{:#?}", monks);
-    let mut ins: Vec<usize> = monks.iter().map(|m| m.inspected).collect();
-    ins.sort();
-    println!("{}", ins.iter().rev().take(2).fold(1, |acc, x| acc * x ));
 }
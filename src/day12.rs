@@ -1,11 +1,21 @@
-use std::collections::HashMap;
 use std::collections::HashSet;
-use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::fmt::Write;
 
+use crate::{
+    error::{Error, ParseError},
+    graph,
+    grid::{Grid, Pos},
+    image::Image,
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 12;
+
 #[derive(Eq, PartialEq)]
-enum MapItem {
+pub enum MapItem {
     Best,
     Square(usize),
 }
@@ -20,156 +30,203 @@ impl Debug for MapItem {
 }
 
 #[derive(Debug)]
-struct HeightMap {
-    grid: Vec<Vec<MapItem>>,
+pub struct HeightMap {
+    pub grid: Grid<MapItem>,
+    pub start: Pos,
 }
 
-impl From<&str> for HeightMap {
-    fn from(input: &str) -> Self {
-        HeightMap {
-            grid: input
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| match c {
-                            'S' => MapItem::Square(('a' as usize) - 50),
-                            'E' => MapItem::Best,
-                            place => MapItem::Square((place as usize) - 50),
-                        })
-                        .collect()
-                })
-                .collect(),
+impl HeightMap {
+    fn height(&self, pos: Pos) -> usize {
+        match &self.grid[pos] {
+            MapItem::Square(height) => *height,
+            MapItem::Best => ('z' as usize) - 50,
         }
     }
+
+    fn best(&self) -> Option<Pos> {
+        let (best, _) = self.grid.iter().find(|(_, item)| **item == MapItem::Best)?;
+        Some(best)
+    }
+
+    /// Every way up from the closest of `starts`, climbing at most one level a step.
+    fn climb(&self, starts: impl IntoIterator<Item = Pos>) -> graph::Paths<Pos, usize> {
+        graph::bfs(starts, |&pos| {
+            self.grid
+                .neighbours4(pos)
+                .filter(move |&next| self.height(next) <= self.height(pos) + 1)
+        })
+    }
+
+    /// The fewest steps to the best signal from any of the lowest squares.
+    pub fn find_best_best(&self) -> Option<usize> {
+        let lowest = self
+            .grid
+            .iter()
+            .filter(|(_, item)| **item == MapItem::Square(('a' as usize) - 50))
+            .map(|(pos, _)| pos);
+        self.climb(lowest).cost(&self.best()?)
+    }
+
+    /// The fewest steps from `start` to the best signal.
+    pub fn find_best(&self, start: Pos) -> Option<usize> {
+        self.climb([start]).cost(&self.best()?)
+    }
+
+    /// The squares on one of the shortest ways from `start` to the best signal, both ends included.
+    pub fn route(&self, start: Pos) -> Option<Vec<Pos>> {
+        self.climb([start]).path(&self.best()?)
+    }
+}
+
+/// Low ground in dark green up to white peaks, with the start in red, the best signal in gold and
+/// the shortest way between them in blue.
+pub fn picture(input: &str) -> Result<Image, ParseError> {
+    let map = HeightMap::parse(input)?;
+    let lowest = ('a' as usize) - 50;
+    let route: HashSet<Pos> = map.route(map.start).into_iter().flatten().collect();
+    Ok(Image::Colour(map.grid.map(|pos, item| match item {
+        _ if pos == map.start => [220, 40, 40],
+        MapItem::Best => [255, 200, 0],
+        _ if route.contains(&pos) => [40, 90, 220],
+        MapItem::Square(height) => {
+            let shade =
+                |from: usize, to: usize| (from + (to - from) * (height - lowest) / 25) as u8;
+            [shade(30, 250), shade(90, 250), shade(40, 250)]
+        }
+    })))
+}
+
+impl Solution for HeightMap {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let chars = Grid::parse(input, "a height (a-z), S or E", |c| {
+            matches!(c, 'a'..='z' | 'S' | 'E').then_some(c)
+        })
+        .map_err(|e| ParseError::unexpected(DAY, input, e))?;
+        let end = &input[input.len()..];
+        let start = chars
+            .iter()
+            .find(|(_, &c)| c == 'S')
+            .map(|(pos, _)| pos)
+            .ok_or_else(|| ParseError::new(DAY, input, end, "expected a start (S)"))?;
+        if !chars.iter().any(|(_, &c)| c == 'E') {
+            return Err(ParseError::new(DAY, input, end, "expected an end (E)"));
+        }
+        let grid = chars.map(|_, &c| match c {
+            'S' => MapItem::Square(('a' as usize) - 50),
+            'E' => MapItem::Best,
+            _ => MapItem::Square((c as usize) - 50),
+        });
+        Ok(HeightMap { grid, start })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let steps = self
+            .find_best(self.start)
+            .ok_or(Error::NoAnswer("there's no way up from S to E"))?;
+        Ok(steps.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let steps = self
+            .find_best_best()
+            .ok_or(Error::NoAnswer("there's no way up to E from elevation a"))?;
+        Ok(steps.into())
+    }
 }
 
 impl HeightMap {
-    fn find_best_best(&self) {
-        let best = self.grid.iter().enumerate().map(|(y, row)| {
-            row.iter().enumerate().filter_map(|(x, item)| {
-                if let MapItem::Square(s) = item {
-                    if s == &(('a' as usize) - 50) {
-                        Some((x as i32, y as i32))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }).collect::<Vec<(i32, i32)>>()
-        }).flatten().filter_map(|start| self.find_best(start)).min();
-        println!("{:?}", best);
-    }
-
-    fn find_best(&self, start: (i32, i32)) -> Option<usize> {
-        println!("Trying with start {:?}", start);
-        let mut que = VecDeque::new();
-        let mut adjacency_list: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::default();
-        let mut explored: HashMap<(i32, i32), (i32, i32)> = HashMap::default();
-        let mut seen: HashSet<(i32, i32)> = HashSet::default();
-        seen.insert(start);
-
-        que.push_back((None::<(i32, i32)>, start));
-        let mut best = None::<(i32, i32)>;
-        while let Some((prev, elem)) = que.pop_front() {
-            if best.is_some() {
-                continue;
-            }
-            if let Some(prev) = prev {
-                if !adjacency_list.contains_key(&prev) {
-                    adjacency_list.insert(prev, Vec::new());
-                }
-                adjacency_list.get_mut(&prev).unwrap().push(elem);
-            }
-            let cur_pos = match &self.grid[elem.1 as usize][elem.0 as usize] {
-                MapItem::Square(height) => height,
-                MapItem::Best => &(('z' as usize) - 50),
-            };
-            'exploring: for y in -1..=1 {
-                let explore_y = elem.1 + y;
-                if explore_y < 0 || explore_y >= self.grid.len() as i32 {
-                    continue;
-                }
-                for x in -1..=1 {
-                    let explore_x = elem.0 + x;
-                    if explore_x < 0 || explore_x >= self.grid[explore_y as usize].len() as i32 {
-                        continue;
-                    }
-                    if (explore_x, explore_y) == elem {
-                        continue;
-                    }
-                    if y != 0 && x != 0 {
-                        continue;
-                    }
-                    if seen.contains(&(explore_x, explore_y)) {
-                        continue;
-                    }
-                    match &self.grid[explore_y as usize][explore_x as usize] {
-                        MapItem::Best => {
-                            if (('z' as usize) - 50) <= (cur_pos + 1) {
-                                que.push_back((Some(elem), (explore_x, explore_y)));
-                                explored.insert((explore_x, explore_y), elem);
-                                seen.insert((explore_x, explore_y));
-                                best = Some((explore_x, explore_y));
-                                break 'exploring;
-                            }
-                        }
-                        MapItem::Square(height) => {
-                            if height <= &(cur_pos + 1) {
-                                que.push_back((Some(elem), (explore_x, explore_y)));
-                                explored.insert((explore_x, explore_y), elem);
-                                seen.insert((explore_x, explore_y));
-                            }
-                        }
+    /// The fewest steps to the best signal from the closest of `starts`, found by taking a step
+    /// off every square's distance that a neighbour can beat until none can.
+    fn relax(&self, starts: impl Iterator<Item = Pos>) -> Option<usize> {
+        let mut steps = self.grid.map(|_, _| None::<usize>);
+        for start in starts {
+            steps[start] = Some(0);
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for pos in self.grid.positions() {
+                let Some(here) = steps[pos] else { continue };
+                for next in self.grid.neighbours4(pos) {
+                    if self.height(next) <= self.height(pos) + 1
+                        && steps[next].is_none_or(|there| there > here + 1)
+                    {
+                        steps[next] = Some(here + 1);
+                        changed = true;
                     }
                 }
             }
         }
-        //println!("{:?}", best);
-        //let mut print_grid = vec![vec!["."; self.grid[0].len()]; self.grid.len()];
-
-        let mut cur = best?;
-        //print_grid[cur.1 as usize][cur.0 as usize] = "E";
-        let mut path = Vec::new();
-        path.push(cur);
-        let mut cycle_detect = HashSet::new();
-        while let Some(prev) = explored.get(&(cur)) {
-            path.push(*prev);
-            // println!(
-            //     "{:?} {:?} {:?}",
-            //     prev,
-            //     cur,
-            //     (prev.0 - cur.0, prev.1 - cur.1)
-            // );
-            if cycle_detect.contains(prev) {
-                panic!("Cycle! {:?} {:?}", cur, prev);
-            }
-            cycle_detect.insert(prev);
-            // match (prev.0 - cur.0, prev.1 - cur.1) {
-            //     (0, -1) => print_grid[prev.1 as usize][prev.0 as usize] = "v", // down
-            //     (0, 1) => print_grid[prev.1 as usize][prev.0 as usize] = "^",  // up
-            //     (1, 0) => print_grid[prev.1 as usize][prev.0 as usize] = "<",  // right
-            //     (-1, 0) => print_grid[prev.1 as usize][prev.0 as usize] = ">", // left
-            //     _ => unreachable!(),
-            // }
-            cur = *prev;
-        }
+        steps[self.best()?]
+    }
+}
+
+impl Reference for HeightMap {
+    fn reference_part1(&self) -> Option<Answer> {
+        self.relax([self.start].into_iter()).map(Answer::from)
+    }
 
-        //for row in print_grid {
-        //    println!("{}", row.join(""));
-        //}
-        Some(path.len() - 1)
+    fn reference_part2(&self) -> Option<Answer> {
+        let lowest = self
+            .grid
+            .iter()
+            .filter(|(_, item)| **item == MapItem::Square(('a' as usize) - 50))
+            .map(|(pos, _)| pos);
+        self.relax(lowest).map(Answer::from)
     }
 }
 
-pub fn main() {
-    let map: HeightMap = include_str!("data/day12.txt").into();
-    println!("{:?}", map);
-    map.find_best_best();
+/// A `size` by `size` map (at least 26 across, to fit every height) rising steadily from the
+/// start on the left to the best signal on the right. A few squares dip a level below their
+/// neighbours, but never along the top row, so there's always a way up.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let width = size.max(26);
+    let height = size.max(1);
+    let (start, end) = (rng.below(height), rng.below(height));
+    let mut out = String::new();
+    for y in 0..height {
+        for x in 0..width {
+            let level = (x * 25 / (width - 1)) as u8;
+            let level = match y > 0 && level > 0 && rng.one_in(8) {
+                true => level - 1,
+                false => level,
+            };
+            out.push(match (x, y) {
+                (0, y) if y == start => 'S',
+                (x, y) if x == width - 1 && y == end => 'E',
+                _ => (b'a' + level) as char,
+            });
+        }
+        out.push('\n');
+    }
+    out
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    #[test]
+    fn test_level_conversion() {
+        let map = HeightMap::parse("abS\nEzc").unwrap();
+        assert_eq!(map.start, (2, 0));
+        // the start is as low as it gets and the best signal as high
+        assert_eq!(map.grid[(2, 0)], MapItem::Square(('a' as usize) - 50));
+        assert_eq!(map.grid[(0, 1)], MapItem::Best);
+        assert_eq!(map.grid[(1, 1)], MapItem::Square(('z' as usize) - 50));
+    }
+
     #[test]
-    fn test_level_conversion() {}
+    fn test_route() {
+        // too steep to climb all the way
+        let map = HeightMap::parse("zzzzz\nSbcdE").unwrap();
+        assert_eq!(map.find_best(map.start), None);
+        // straight along the bottom, as the top is too high to get onto until the end
+        let map =
+            HeightMap::parse("zzzzzzzzzzzzzzzzzzzzzzzzzz\nSbcdefghijklmnopqrstuvwxyE").unwrap();
+        assert_eq!(map.find_best(map.start), Some(25));
+        let route = map.route(map.start).unwrap();
+        assert_eq!(route.len(), 26);
+        assert!(route.iter().enumerate().all(|(x, &pos)| pos == (x, 1)));
+    }
 }
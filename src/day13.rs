@@ -1,3 +1,212 @@
-pub fn main() {
+use std::cmp::Ordering;
 
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, newline},
+    combinator::{map, opt},
+    error::ErrorKind,
+    multi::{separated_list0, separated_list1},
+    sequence::{delimited, separated_pair, terminated},
+    IResult,
+};
+
+use crate::{
+    error::{Error, ParseError},
+    parsers::{self, unsigned},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 13;
+
+/// How many lists deep a packet can go. Parsing and comparing go one call deeper for each, so
+/// without a limit a made-up input could run them out of stack; the puzzle's go less than ten.
+pub const MAX_DEPTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Int(u32),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        Self::parse_nested(input, 0)
+    }
+
+    /// A packet inside `depth` lists.
+    fn parse_nested(input: &str, depth: usize) -> IResult<&str, Self> {
+        if depth == MAX_DEPTH && input.starts_with('[') {
+            let too_deep = nom::error::Error::new(input, ErrorKind::TooLarge);
+            return Err(nom::Err::Failure(too_deep));
+        }
+        let item = |input| Self::parse_nested(input, depth + 1);
+        alt((
+            map(unsigned, Packet::Int),
+            map(
+                delimited(char('['), separated_list0(char(','), item), char(']')),
+                Packet::List,
+            ),
+        ))(input)
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+            // element by element, and if one runs out first it's the smaller -- which is exactly
+            // how slices already compare
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+            // exactly one side is an int, so it gets wrapped in a list
+            (Packet::Int(a), list) => Packet::List(vec![Packet::Int(*a)]).cmp(list),
+            (list, Packet::Int(b)) => list.cmp(&Packet::List(vec![Packet::Int(*b)])),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub struct Signal {
+    pub pairs: Vec<(Packet, Packet)>,
+}
+
+impl Signal {
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, pairs) = terminated(
+            separated_list1(
+                tag("\n\n"),
+                separated_pair(Packet::parse, newline, Packet::parse),
+            ),
+            opt(newline),
+        )(input)?;
+        Ok((input, Signal { pairs }))
+    }
+}
+
+impl Solution for Signal {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        parsers::all(DAY, input, Signal::parse)
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let ordered: usize = self
+            .pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, (left, right))| left < right)
+            .map(|(i, _)| i + 1)
+            .sum();
+        Ok(ordered.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let first_break = Packet::List(vec![Packet::List(vec![Packet::Int(2)])]);
+        let second_break = Packet::List(vec![Packet::List(vec![Packet::Int(6)])]);
+        let mut all_packs: Vec<&Packet> = self
+            .pairs
+            .iter()
+            .flat_map(|(left, right)| [left, right])
+            .chain([&first_break, &second_break])
+            .collect();
+        all_packs.sort();
+        let position = |pack: &Packet| all_packs.iter().position(|&p| p == pack).unwrap() + 1;
+        Ok((position(&first_break) * position(&second_break)).into())
+    }
+}
+
+fn random_packet(rng: &mut Rng, depth: usize) -> String {
+    if depth > 0 && !rng.one_in(3) {
+        let items: Vec<String> = (0..rng.below(5))
+            .map(|_| random_packet(rng, depth - 1))
+            .collect();
+        return format!("[{}]", items.join(","));
+    }
+    rng.below(11).to_string()
+}
+
+/// `size` pairs of packets, nested up to four lists deep.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            // packets are always lists on the outside
+            let left = format!("[{}]", random_packet(rng, 3));
+            let right = format!("[{}]", random_packet(rng, 3));
+            format!("{}\n{}\n", left, right)
+        })
+        .collect();
+    pairs.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{check_parser, whole, Rng};
+
+    #[test]
+    fn test_packet_order() {
+        let parse = |s| Packet::parse(s).unwrap().1;
+        assert!(parse("[1,1,3,1,1]") < parse("[1,1,5,1,1]"));
+        assert!(parse("[[1],[2,3,4]]") < parse("[[1],4]"));
+        assert!(parse("[9]") > parse("[[8,7,6]]"));
+        assert!(parse("[[4,4],4,4]") < parse("[[4,4],4,4,4]"));
+        assert!(parse("[7,7,7,7]") > parse("[7,7,7]"));
+        assert!(parse("[]") < parse("[3]"));
+        assert!(parse("[[[]]]") > parse("[[]]"));
+    }
+
+    #[test]
+    fn test_too_deep() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(whole(Packet::parse(&nested(MAX_DEPTH))).is_some());
+        for depth in [MAX_DEPTH + 1, 100_000] {
+            let input = format!("{}\n[]\n", nested(depth));
+            let Err(err) = <Signal as Solution>::parse(&input) else {
+                panic!("{} lists deep parsed", depth);
+            };
+            assert_eq!((err.column, err.message.as_str()), (101, "nested too deep"));
+        }
+    }
+
+    fn random_packet(rng: &mut Rng, depth: usize) -> Packet {
+        if depth > 0 && !rng.one_in(3) {
+            let items = (0..rng.below(4)).map(|_| random_packet(rng, depth - 1));
+            return Packet::List(items.collect());
+        }
+        // mostly small, like the puzzle's, but now and then as big as they go
+        match rng.one_in(10) {
+            true => Packet::Int(rng.next_u64() as u32),
+            false => Packet::Int(rng.below(11) as u32),
+        }
+    }
+
+    fn text(packet: &Packet) -> String {
+        match packet {
+            Packet::Int(n) => n.to_string(),
+            Packet::List(items) => {
+                let items: Vec<_> = items.iter().map(text).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+
+    #[test]
+    fn test_packet_round_trip() {
+        check_parser(
+            300,
+            "],x \n",
+            |rng| {
+                let packet = Packet::List(vec![random_packet(rng, 4)]);
+                let text = text(&packet);
+                (packet, text)
+            },
+            text,
+            |text| whole(Packet::parse(text)),
+        );
+    }
 }
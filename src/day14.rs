@@ -1,5 +1,29 @@
+use std::collections::HashSet;
 
-const X_LEN: usize = 10000;
+use nom::{bytes::complete::tag, multi::separated_list1};
+
+use crate::{
+    animate::{Frame, Simulation},
+    error::{Error, ParseError},
+    geometry::Point,
+    grid::Grid,
+    image::{Image, Palette},
+    parsers::{self, point, unsigned},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 14;
+
+const PALETTE: Palette = Palette {
+    colours: &[
+        ('.', [20, 20, 40]),
+        ('#', [120, 100, 80]),
+        ('o', [230, 200, 120]),
+    ],
+    other: [255, 80, 0],
+};
 
 #[derive(Clone, Debug, PartialEq)]
 enum Space {
@@ -7,86 +31,84 @@ enum Space {
     Sand,
     Rock,
 }
-struct Grid {
-    grid: Vec<Vec<Space>>,
-    floor_y: usize,
-    sand_starts_at: Point,
-    moving_sand: Option<Point>,
+struct Reservoir {
+    /// Only as wide as the rock and the pile of sand on the floor can reach; column 0 is
+    /// `x_offset` in the scan's coordinates.
+    spaces: Grid<Space>,
+    x_offset: usize,
+    floor_y: Option<usize>,
+    sand_starts_at: Point<usize>,
+    moving_sand: Option<Point<usize>>,
     tot_grains: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl From<(usize, usize)> for Point {
-    fn from(pair: (usize, usize)) -> Self {
-        Self {
-            x: pair.0,
-            y: pair.1,
-        }
-    }
-}
-
-impl From<&Vec<Vec<Point>>> for Grid {
-    fn from(paths: &Vec<Vec<Point>>) -> Self {
-        let max_y = paths
-            .iter()
-            .flat_map(|pairs| pairs.iter().map(|pair| pair.y))
-            .max()
-            .unwrap();
-        let mut grid = vec![vec![Space::Empty; X_LEN]; max_y + 1 + 3];
+impl From<&Vec<Vec<Point<usize>>>> for Reservoir {
+    fn from(paths: &Vec<Vec<Point<usize>>>) -> Self {
+        let points = || paths.iter().flatten();
+        let max_y = points().map(|point| point.y).max().unwrap();
+        let floor_y = max_y + 2;
+        // sand piles up on the floor no further out than it falls, plus a column either side for
+        // it to fall past
+        let min_x = points().map(|point| point.x).min().unwrap().min(500);
+        let max_x = points().map(|point| point.x).max().unwrap().max(500);
+        let x_offset = min_x.saturating_sub(floor_y + 1);
+        let width = max_x + floor_y + 2 - x_offset;
+        let mut spaces = Grid::new(width, floor_y + 1, Space::Empty);
         for path in paths {
             for i in 0..(path.len() - 1) {
                 let pair = (&path[i], &path[i + 1]);
                 let minpair_y = pair.0.y.min(pair.1.y);
                 let maxpair_y = pair.0.y.max(pair.1.y);
                 for y in minpair_y..=maxpair_y {
-                    grid[y][pair.0.x + (X_LEN / 2)] = Space::Rock;
+                    spaces[(pair.0.x - x_offset, y)] = Space::Rock;
                 }
                 let minpair_x = pair.0.x.min(pair.1.x);
                 let maxpair_x = pair.0.x.max(pair.1.x);
                 for x in minpair_x..=maxpair_x {
-                    grid[pair.0.y][x + (X_LEN / 2)] = Space::Rock;
+                    spaces[(x - x_offset, pair.0.y)] = Space::Rock;
                 }
             }
         }
-        Grid {
-            grid,
-            moving_sand: Some(((X_LEN / 2) + 500, 0).into()),
-            sand_starts_at: ((X_LEN / 2) + 500, 0).into(),
-            floor_y: max_y + 2,
+        Reservoir {
+            spaces,
+            x_offset,
+            moving_sand: Some((500, 0).into()),
+            sand_starts_at: (500, 0).into(),
+            floor_y: Some(floor_y),
             tot_grains: 0,
         }
     }
 }
-impl From<Vec<Vec<Point>>> for Grid {
-    fn from(paths: Vec<Vec<Point>>) -> Self {
+impl From<Vec<Vec<Point<usize>>>> for Reservoir {
+    fn from(paths: Vec<Vec<Point<usize>>>) -> Self {
         (&paths).into()
     }
 }
 
-impl Grid {
+impl Reservoir {
+    /// Without a floor, sand falls off the bottom of the grid instead.
+    fn without_floor(self) -> Self {
+        Self {
+            floor_y: None,
+            ..self
+        }
+    }
+    /// Takes `x` in the scan's coordinates; anything off the grid is the abyss.
     fn get_coord(&self, x: usize, y: usize) -> Option<&Space> {
-        if y >= self.grid.len() || x >= self.grid[0].len() {
-            None
+        let space = self.spaces.get((x.checked_sub(self.x_offset)?, y))?;
+        if self.floor_y.is_some_and(|floor_y| y >= floor_y) {
+            Some(&Space::Rock)
         } else {
-            if y >= self.floor_y {
-                Some(&Space::Rock)
-            } else {
-                let row = &self.grid[y];
-                //println!("{:?}", row);
-                Some(&row[x])
-            }
+            Some(space)
         }
     }
-    fn step(&mut self) -> Option<()> {
+    /// Moves the falling grain of sand, or starts the next one.
+    fn fall(&mut self) -> Option<()> {
         if let Some(sand) = self.moving_sand {
             for option in [
                 (sand.x, sand.y + 1),
-                (sand.x - 1, sand.y + 1),
+                // off the left edge is the abyss too, which `get_coord` sees as far off the right
+                (sand.x.wrapping_sub(1), sand.y + 1),
                 (sand.x + 1, sand.y + 1),
             ] {
                 if let Space::Empty = self.get_coord(option.0, option.1)? {
@@ -98,7 +120,7 @@ impl Grid {
                 return None;
             }
             // we've hit a wall -- and can't move! so sand is now solid sand
-            self.grid[sand.y][sand.x] = Space::Sand;
+            self.spaces[(sand.x - self.x_offset, sand.y)] = Space::Sand;
             self.moving_sand = None;
             Some(())
         } else {
@@ -107,75 +129,178 @@ impl Grid {
             Some(())
         }
     }
-    fn print(&self) {
-        let mut print_start = 10000;
-        let mut print_end = 0;
-        for row in &self.grid {
-            for (i, elem) in row.iter().enumerate() {
-                if let Space::Empty = elem {
-                    continue;
-                }
-                print_start = print_start.min(i);
-                print_end = print_end.max(i);
+    /// How many grains have come to rest once [`Reservoir::fall`] is done. The first grain was
+    /// never counted, and the last one was: it's either still falling into the abyss, or stuck
+    /// where it started and at rest after all.
+    fn grains_at_rest(&self) -> usize {
+        match self.moving_sand == Some(self.sand_starts_at) {
+            true => self.tot_grains + 1,
+            false => self.tot_grains,
+        }
+    }
+}
+
+impl Simulation for Reservoir {
+    fn step(&mut self) -> bool {
+        self.fall().is_some()
+    }
+
+    fn frame(&self) -> Frame {
+        let mut picture = self.spaces.map(|_, space| match *space {
+            Space::Rock => '#',
+            Space::Sand => 'o',
+            Space::Empty => '.',
+        });
+        if let Some(floor_y) = self.floor_y {
+            for x in 0..picture.width() {
+                picture[(x, floor_y)] = '#';
             }
         }
-        for (y, row) in self.grid.iter().enumerate() {
-            for (x, elem) in row.iter().enumerate() {
-                if x <= print_start || x > print_end {
-                    continue;
+        let focus = self.moving_sand.map(|sand| (sand.x - self.x_offset, sand.y));
+        if let Some(focus) = focus {
+            picture[focus] = '+';
+        }
+        Frame { picture, focus }
+    }
+
+    fn palette(&self) -> Palette {
+        PALETTE
+    }
+}
+
+/// The cave once the sand has piled up to the top, as in part 2.
+pub fn picture(input: &str) -> Result<Image, ParseError> {
+    let mut reservoir = Reservoir::from(parse_paths(input)?);
+    while reservoir.fall().is_some() {}
+    Ok(Image::from_chars(&reservoir.frame().picture, &PALETTE))
+}
+
+/// Animates sand filling the cave with a floor, as in part 2.
+pub fn animate(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Reservoir::from(parse_paths(input)?)))
+}
+
+pub fn parse_paths(input: &str) -> Result<Vec<Vec<Point<usize>>>, ParseError> {
+    let paths = parsers::each_line(DAY, input, separated_list1(tag(" -> "), point(unsigned)))?;
+    if paths.is_empty() {
+        return Err(ParseError::new(
+            DAY,
+            input,
+            input,
+            "expected a path of rock",
+        ));
+    }
+    Ok(paths)
+}
+
+pub struct Cave {
+    pub paths: Vec<Vec<Point<usize>>>,
+}
+
+impl Solution for Cave {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            paths: parse_paths(input)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let mut grid = Reservoir::from(&self.paths).without_floor();
+        while grid.fall().is_some() {}
+        Ok(grid.grains_at_rest().into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let mut grid = Reservoir::from(&self.paths);
+        while grid.fall().is_some() {}
+        Ok(grid.grains_at_rest().into())
+    }
+}
+
+impl Cave {
+    /// How many grains of sand come to rest, dropped one at a time onto a set of every blocked
+    /// point. Without a floor, that's until one falls past the lowest rock.
+    fn pour(&self, floor: bool) -> usize {
+        let mut blocked = HashSet::new();
+        for path in &self.paths {
+            for pair in path.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    for y in a.y.min(b.y)..=a.y.max(b.y) {
+                        blocked.insert(Point::new(x, y));
+                    }
                 }
-                if Some((x, y).into()) == self.moving_sand {
-                    print!("+");
-                    continue;
+            }
+        }
+        let lowest = self.paths.iter().flatten().map(|p| p.y).max().unwrap();
+        let source = Point::new(500, 0);
+        let mut grains = 0;
+        while !blocked.contains(&source) {
+            let mut sand = source;
+            loop {
+                if !floor && sand.y > lowest {
+                    return grains;
                 }
-                match elem {
-                    &Space::Rock => print!("#"),
-                    &Space::Sand => print!("o"),
-                    &Space::Empty => print!("."),
+                let next = [sand.x, sand.x - 1, sand.x + 1]
+                    .map(|x| Point::new(x, sand.y + 1))
+                    .into_iter()
+                    .find(|p| !blocked.contains(p) && (!floor || p.y < lowest + 2));
+                match next {
+                    Some(next) => sand = next,
+                    None => break,
                 }
             }
-            println!();
+            blocked.insert(sand);
+            grains += 1;
         }
+        grains
     }
 }
 
-pub fn main() {
-    let mut paths: Grid = include_str!("data/day14.txt")
-        .lines()
-        .map(|line| {
-            line.split(" -> ")
-                .map(|pair| {
-                    let pairs: Vec<_> = pair.split(",").collect();
-                    assert_eq!(2, pairs.len());
-                    (pairs[0].parse().unwrap(), pairs[1].parse().unwrap()).into()
-                })
-                .collect()
+impl Reference for Cave {
+    fn reference_part1(&self) -> Option<Answer> {
+        Some(self.pour(false).into())
+    }
+
+    fn reference_part2(&self) -> Option<Answer> {
+        Some(self.pour(true).into())
+    }
+}
+
+/// `size` paths of rock, each a few horizontal and vertical lines, spread out under the source
+/// of the sand and deeper the more there are.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let depth = 10 + size;
+    (0..size.max(1))
+        .map(|_| {
+            let mut at = Point::new(450 + rng.below(100), 2 + rng.below(depth));
+            let mut points = vec![format!("{},{}", at.x, at.y)];
+            let mut across = rng.one_in(2);
+            for _ in 0..1 + rng.below(4) {
+                let length = 1 + rng.below(8);
+                match (across, rng.one_in(2)) {
+                    (true, true) => at.x += length,
+                    (true, false) => at.x -= length,
+                    (false, true) => at.y += length,
+                    // never up as far as the source
+                    (false, false) => at.y = at.y.saturating_sub(length).max(2),
+                }
+                across = !across;
+                points.push(format!("{},{}", at.x, at.y));
+            }
+            points.join(" -> ") + "\n"
         })
-        .collect::<Vec<Vec<Point>>>()
-        .into();
-    while let Some(_) = paths.step() {}
-    paths.print();
-    println!("{}", paths.tot_grains + 1); // idk why +1 I think there's an off by one error
-                                          // somewhere
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    fn make_grid() -> (Vec<Vec<Point>>, Grid) {
-        let points: Vec<Vec<Point>> = include_str!("data/day14.txt")
-            .lines()
-            .map(|line| {
-                line.split(" -> ")
-                    .map(|pair| {
-                        let pairs: Vec<_> = pair.split(",").collect();
-                        assert_eq!(2, pairs.len());
-                        (pairs[0].parse().unwrap(), pairs[1].parse().unwrap()).into()
-                    })
-                    .collect()
-            })
-            .collect();
-        let grid: Grid = (&points).into();
+    use crate::rng::check_parser;
+    fn make_grid() -> (Vec<Vec<Point<usize>>>, Reservoir) {
+        let points =
+            parse_paths("498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9").unwrap();
+        let grid: Reservoir = (&points).into();
         (points, grid)
     }
     #[test]
@@ -185,4 +310,36 @@ mod test {
             assert_eq!(Some(&Space::Rock), grid.get_coord(point.x, point.y));
         }
     }
+
+    fn text(paths: &[Vec<Point<usize>>]) -> String {
+        let lines: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                let points: Vec<_> = path.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                points.join(" -> ")
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_paths_round_trip() {
+        check_parser(
+            200,
+            "-> ,x\n",
+            |rng| {
+                let paths: Vec<Vec<Point<usize>>> = (0..1 + rng.below(5))
+                    .map(|_| {
+                        (0..1 + rng.below(6))
+                            .map(|_| Point::new(rng.below(1000), rng.below(200)))
+                            .collect()
+                    })
+                    .collect();
+                let text = text(&paths);
+                (paths, text)
+            },
+            |paths| text(paths),
+            |text| parse_paths(text).ok(),
+        );
+    }
 }
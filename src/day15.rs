@@ -2,81 +2,62 @@ use std::collections::{HashMap, HashSet};
 
 use nom::{
     branch::alt,
-    bytes::streaming::tag,
-    character::complete::digit1,
-    combinator::opt,
+    bytes::complete::tag,
+    combinator::map,
     sequence::{preceded, tuple},
     IResult,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+use crate::{
+    error::{Error, ParseError},
+    geometry::Point,
+    interval::IntervalSet,
+    parsers::{self, signed, unsigned},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
 
-impl From<(i32, i32)> for Point {
-    fn from(vals: (i32, i32)) -> Self {
-        Self {
-            x: vals.0,
-            y: vals.1,
-        }
-    }
-}
+const DAY: u8 = 15;
 
-impl Point {
-    fn from_str(input: &str) -> IResult<&str, Self> {
-        let (input, (x, _, y)) = tuple((
-            preceded(tag("x="), parse_signed_int),
-            tag(", "),
-            preceded(tag("y="), parse_signed_int),
-        ))(input)?;
-        Ok((input, Point { x, y }))
-    }
+fn parse_point(input: &str) -> IResult<&str, Point<i32>> {
+    let (input, (x, _, y)) = tuple((
+        preceded(tag("x="), signed),
+        tag(", "),
+        preceded(tag("y="), signed),
+    ))(input)?;
+    Ok((input, Point { x, y }))
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
-struct Sensor {
-    loc: Point,
-    closest_beacon: Point,
-    dist_to_closest_beacon: i32,
+/// `Row y=10, area 0..=20`: the row part 1 asks about and how far part 2 searches.
+fn parse_area(input: &str) -> IResult<&str, (i32, i32)> {
+    let (input, (row, _, max)) = tuple((
+        preceded(tag("Row y="), signed),
+        tag(", area 0..="),
+        unsigned,
+    ))(input)?;
+    Ok((input, (row, max)))
 }
 
-fn parse_signed_int(input: &str) -> IResult<&str, i32> {
-    let (input, (sign, number)) = tuple((opt(alt((tag("-"), tag("+")))), digit1))(input)?;
-    Ok((
-        input,
-        match sign {
-            None | Some("+") => number.parse().unwrap(),
-            Some("-") => -1 * number.parse::<i32>().unwrap(),
-            _ => unreachable!(),
-        },
-    ))
-}
-
-fn dist(point1: &Point, point2: &Point) -> i32 {
-    (point1.x - point2.x).abs() + (point1.y - point2.y).abs()
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct Sensor {
+    pub loc: Point<i32>,
+    pub closest_beacon: Point<i32>,
+    pub dist_to_closest_beacon: i32,
 }
 
 impl Sensor {
-    fn might_have_beacon(&self, point: &Point) -> bool {
+    pub fn might_have_beacon(&self, point: &Point<i32>) -> bool {
         point == &self.closest_beacon || self.distance(point) > self.dist_to_closest_beacon
     }
-    fn distance(&self, point: &Point) -> i32 {
-        dist(&self.loc, point)
-    }
-    fn push_x_right(&self, point: &Point) -> Point {
-        let dx = self.dist_to_closest_beacon - (self.loc.y - point.y).abs();
-        Point {
-            x: self.loc.x + dx,
-            y: point.y,
-        }
+    pub fn distance(&self, point: &Point<i32>) -> i32 {
+        self.loc.manhattan(point)
     }
     fn from_str(input: &str) -> IResult<&str, Self> {
-        let (input, loc) = preceded(tag("Sensor at "), Point::from_str)(input)?;
+        let (input, loc) = preceded(tag("Sensor at "), parse_point)(input)?;
         let (input, closest_beacon) =
-            preceded(tag(": closest beacon is at "), Point::from_str)(input)?;
-        let dist_to_closest_beacon = dist(&loc, &closest_beacon);
+            preceded(tag(": closest beacon is at "), parse_point)(input)?;
+        let dist_to_closest_beacon = loc.manhattan(&closest_beacon);
         Ok((
             input,
             Sensor {
@@ -88,132 +69,231 @@ impl Sensor {
     }
 }
 
-fn _get_min_maxes(sensors: &Vec<&Sensor>) -> Option<(i32, i32, i32, i32)> {
-    let min_x = sensors
-        .iter()
-        .flat_map(|sensor| {
-            vec![
-                sensor.loc.x - sensor.dist_to_closest_beacon,
-                sensor.loc.x + sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.x - sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.x + sensor.dist_to_closest_beacon,
-            ]
-        })
-        .min()?;
-    let max_x = sensors
-        .iter()
-        .flat_map(|sensor| {
-            vec![
-                sensor.loc.x - sensor.dist_to_closest_beacon,
-                sensor.loc.x + sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.x - sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.x + sensor.dist_to_closest_beacon,
-            ]
-        })
-        .max()?;
-    let min_y = sensors
-        .iter()
-        .flat_map(|sensor| {
-            vec![
-                sensor.loc.y - sensor.dist_to_closest_beacon,
-                sensor.loc.y + sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.y - sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.y + sensor.dist_to_closest_beacon,
-            ]
-        })
-        .min()?;
-    let max_y = sensors
-        .iter()
-        .flat_map(|sensor| {
-            vec![
-                sensor.loc.y - sensor.dist_to_closest_beacon,
-                sensor.loc.y + sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.y - sensor.dist_to_closest_beacon,
-                sensor.closest_beacon.y + sensor.dist_to_closest_beacon,
-            ]
-        })
-        .max()?;
-    Some((min_x, max_x, min_y, max_y))
+pub struct Sensors {
+    pub sensors: HashMap<Point<i32>, Sensor>,
+    pub beacons: HashSet<Point<i32>>,
+    /// The row part 1 asks about, 2000000 unless the input starts with a `Row y=<row>, area
+    /// 0..=<max>` line, as generated inputs do.
+    pub row_to_test: i32,
+    /// Part 2's beacon has both coordinates between 0 and this, 4000000 unless the input says.
+    pub max: i32,
 }
 
-pub fn main() {
-    // when you enter an area known by a sensor, you can use the distance to figure out how far you
-    // can skip -- save a lot of time by just not checking large aress
-    let sensors: HashMap<_, _> = include_str!("data/day15.txt")
-        .lines()
-        .map(|line| {
-            let (input, sensor) = Sensor::from_str(line).unwrap();
-            assert_eq!(input.len(), 0);
-            (sensor.loc, sensor)
-        })
-        .collect();
-    let beacons: HashSet<_> = sensors.values().map(|s| s.closest_beacon).collect();
-
-    let (_min, max) = (0, 4000000);
-    // let batch_size = 400000;
-    let mut cur_pos = Point { x: 0, y: 0 };
-    loop {
-        match sensors
+impl Sensors {
+    /// The stretches of row `y` that some sensor can see.
+    fn coverage(&self, y: i32) -> IntervalSet {
+        self.sensors
             .values()
-            .filter(|s| !s.might_have_beacon(&cur_pos))
-            .map(|s| s.push_x_right(&cur_pos))
-            .max_by_key(|new_point| new_point.x)
-        {
-            None => {
-                if beacons.contains(&cur_pos) {
-                    cur_pos.x += 1;
-                    continue;
+            .filter_map(|sensor| {
+                let reach = sensor.dist_to_closest_beacon - (sensor.loc.y - y).abs();
+                let x = sensor.loc.x as i64;
+                (reach >= 0).then(|| x - reach as i64..=x + reach as i64)
+            })
+            .collect()
+    }
+}
+
+impl Solution for Sensors {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        // a sensor last, so that a line that's neither is reported as a broken sensor
+        let line = alt((map(parse_area, Err), map(Sensor::from_str, Ok)));
+        let mut area = (2000000, 4000000);
+        let mut sensors = HashMap::new();
+        let lines = parsers::each_line(DAY, input, line)?;
+        for (i, line) in lines.into_iter().enumerate() {
+            match line {
+                Ok(sensor) => {
+                    sensors.insert(sensor.loc, sensor);
+                }
+                Err(given) if i == 0 => area = given,
+                Err(_) => {
+                    let at = input.lines().nth(i).unwrap_or_default();
+                    return Err(ParseError::new(DAY, input, at, "expected the area first"));
                 }
-                println!(
-                    "{:?} -> {}",
-                    (cur_pos.x, cur_pos.y),
-                    ((cur_pos.x as i64) * 4000000) + cur_pos.y as i64
-                );
-                break;
             }
-            Some(new_cur_pos) => {
-                if new_cur_pos == cur_pos {
-                    // no new jump, so either linewrap or just step
-                    if cur_pos.x >= max {
-                        if cur_pos.y >= max {
-                            break;
-                        }
-                        if (cur_pos.y + 1) % 1000 == 0 {
-                            println!("{:?}", cur_pos);
-                        }
-                        cur_pos.x = 0;
-                        cur_pos.y += 1;
-                    } else {
-                        cur_pos.x += 1;
-                    }
-                } else {
-                    cur_pos = new_cur_pos
+        }
+        let beacons = sensors.values().map(|s| s.closest_beacon).collect();
+        let (row_to_test, max) = area;
+        Ok(Self {
+            sensors,
+            beacons,
+            row_to_test,
+            max,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        // the beacons the sensors can see are the only ones that can be there
+        let beacons: IntervalSet = self
+            .beacons
+            .iter()
+            .filter(|beacon| beacon.y == self.row_to_test)
+            .map(|beacon| beacon.x as i64..=beacon.x as i64)
+            .collect();
+        let covered = self.coverage(self.row_to_test).difference(&beacons).len();
+        Ok(covered.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        // the first row with a gap in it has the beacon in the gap
+        let max = self.max as i64;
+        let frequency = (0..=self.max)
+            .find_map(|y| {
+                let x = self
+                    .coverage(y)
+                    .gaps(0..=max)
+                    .ranges()
+                    .flatten()
+                    .find(|&x| !self.beacons.contains(&Point::new(x as i32, y)))?;
+                Some(x * 4000000 + y as i64)
+            })
+            .ok_or(Error::NoAnswer("there's no spot for the distress beacon"))?;
+        Ok(frequency.into())
+    }
+}
+
+impl Reference for Sensors {
+    /// Writes down every spot on the row that each sensor can see, then crosses off the beacons.
+    fn reference_part1(&self) -> Option<Answer> {
+        let mut ruled_out = HashSet::new();
+        for sensor in self.sensors.values() {
+            let reach = sensor.dist_to_closest_beacon - (sensor.loc.y - self.row_to_test).abs();
+            ruled_out.extend(sensor.loc.x - reach..=sensor.loc.x + reach);
+        }
+        for beacon in &self.beacons {
+            if beacon.y == self.row_to_test {
+                ruled_out.remove(&beacon.x);
+            }
+        }
+        Some(ruled_out.len().into())
+    }
+
+    /// Tries every spot in turn, the same way round as the solution looks.
+    fn reference_part2(&self) -> Option<Answer> {
+        for y in 0..=self.max {
+            for x in 0..=self.max {
+                let point = Point { x, y };
+                let unseen = |s: &Sensor| s.distance(&point) > s.dist_to_closest_beacon;
+                if !self.beacons.contains(&point) && self.sensors.values().all(unseen) {
+                    return Some((x as i64 * 4000000 + y as i64).into());
                 }
             }
         }
+        None
+    }
+}
+
+/// A sensor at `loc` with its beacon somewhere `distance` away.
+fn sensor_at(rng: &mut Rng, loc: Point<i32>, distance: i32) -> Sensor {
+    let dx = rng.range(-distance as i64..=distance as i64) as i32;
+    let dy = (distance - dx.abs()) * *rng.pick(&[-1, 1]);
+    Sensor {
+        loc,
+        closest_beacon: Point::new(loc.x + dx, loc.y + dy),
+        dist_to_closest_beacon: distance,
+    }
+}
+
+/// `size` sensors scattered over an area that grows with `size`, that between them see every
+/// spot in it but one away from the edges, where part 2's beacon is. Unlike the puzzle, a sensor
+/// can see other sensors' beacons.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let max = 4 * size.max(1) as i32 + 4;
+    let coordinate = |rng: &mut Rng| rng.range(1..=max as i64 - 1) as i32;
+    let hidden = Point::new(coordinate(rng), coordinate(rng));
+    let row = rng.range(0..=max as i64);
+    // a sensor off each diagonal from the hidden spot, seeing as far as it can without seeing
+    // it, sees everything within `max` of it but it
+    let mut sensors: Vec<Sensor> = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .into_iter()
+        .map(|(dx, dy)| {
+            let k = rng.range(max as i64..=2 * max as i64) as i32;
+            let loc = Point::new(hidden.x + dx * k, hidden.y + dy * k);
+            sensor_at(rng, loc, 2 * k - 1)
+        })
+        .collect();
+    // and the rest are inside, none of them seeing it either
+    while sensors.len() < size.max(1) + 4 {
+        let loc = Point::new(coordinate(rng), coordinate(rng));
+        let out_of_sight = loc.manhattan(&hidden) - 1;
+        if out_of_sight > 0 && sensors.iter().all(|s| s.loc != loc) {
+            let distance = rng.range(1..=out_of_sight as i64) as i32;
+            sensors.push(sensor_at(rng, loc, distance));
+        }
+    }
+    rng.shuffle(&mut sensors);
+    let mut text = format!("Row y={}, area 0..={}\n", row, max);
+    for s in &sensors {
+        text += &format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+            s.loc.x, s.loc.y, s.closest_beacon.x, s.closest_beacon.y
+        );
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{check_parser, forall, whole};
+
+    fn text(sensor: &Sensor) -> String {
+        let (loc, beacon) = (sensor.loc, sensor.closest_beacon);
+        format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            loc.x, loc.y, beacon.x, beacon.y
+        )
+    }
+
+    #[test]
+    fn test_sensor_round_trip() {
+        check_parser(
+            200,
+            "Sensor atxy=-0123456789, :",
+            |rng| {
+                let mut point = || {
+                    let mut coordinate = || rng.range(-5_000_000..=5_000_000) as i32;
+                    Point::new(coordinate(), coordinate())
+                };
+                let (loc, closest_beacon) = (point(), point());
+                let sensor = Sensor {
+                    loc,
+                    closest_beacon,
+                    dist_to_closest_beacon: loc.manhattan(&closest_beacon),
+                };
+                let text = text(&sensor);
+                (sensor, text)
+            },
+            text,
+            |text| whole(Sensor::from_str(text)),
+        );
+    }
+
+    #[test]
+    fn test_generated_area_hides_one_spot() {
+        forall(20, |rng| {
+            let size = rng.below(30);
+            let sensors = Sensors::parse(&generate(rng, size)).unwrap();
+            assert_eq!(sensors.max, 4 * size.max(1) as i32 + 4);
+            let hidden: Vec<_> = (0..=sensors.max)
+                .flat_map(|y| (0..=sensors.max).map(move |x| Point { x, y }))
+                .filter(|point| sensors.sensors.values().all(|s| s.might_have_beacon(point)))
+                .collect();
+            assert_eq!(hidden.len(), 1, "{:?}", hidden);
+            let Point { x, y } = hidden[0];
+            assert!(0 < x && x < sensors.max && 0 < y && y < sensors.max);
+        });
+    }
+
+    #[test]
+    fn test_area_comes_first() {
+        let sensor = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+        let sensors = Sensors::parse(&format!("Row y=10, area 0..=20\n{}\n", sensor)).unwrap();
+        assert_eq!((sensors.row_to_test, sensors.max), (10, 20));
+        let Err(err) = Sensors::parse(&format!("{}\nRow y=10, area 0..=20\n", sensor)) else {
+            panic!("the area was taken from the second line");
+        };
+        assert_eq!(err.line, 2);
+        assert_eq!(err.message, "expected the area first");
     }
-    //for y in min..=max {
-    //    for x in min..=max {
-    //        let point = Point { x, y };
-    //        if !sensors.values().any(|s| !s.might_have_beacon(&point)) {
-    //            println!("{:?} -> {}", (x, y), (x * 4000000) + y);
-    //        }
-    //    }
-    //    if y % 1000 == 0 {
-    //        println!("{}", y)
-    //    }
-    //}
-
-    // let row_to_test = 2000000;
-    // let (min_x, max_x, _min_y, _max_y) = get_min_maxes(&sensors.values().collect()).unwrap();
-    // let mut do_not_have = 0;
-    // for x in min_x..=max_x {
-    //     if !sensors
-    //         .values()
-    //         .all(|s| s.might_have_beacon(&Point { x, y: row_to_test }))
-    //     {
-    //         do_not_have += 1;
-    //     }
-    // }
-    // println!("{}", do_not_have);
 }
@@ -1,38 +1,44 @@
-use std::collections::HashMap;
+use std::{cmp::Reverse, collections::HashMap};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit1, newline},
+    character::complete::{alpha1, newline},
     combinator::opt,
     multi::many1,
-    sequence::{preceded, terminated, tuple},
+    sequence::{preceded, tuple},
     IResult,
 };
 
-#[derive(Debug, Clone)]
-enum ValveState {
-    Open,
-    Closed,
-}
+use crate::{
+    bitset::BitSet,
+    error::{Error, ParseError},
+    graph,
+    parsers::{self, comma_list, unsigned},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 16;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Valve {
     id: String,
     rate: usize,
-    state: ValveState,
 }
+
 fn parse_line(line: &str) -> IResult<&str, (Valve, Vec<&str>)> {
     let (line, (id, rate)) = tuple((
         preceded(tag("Valve "), alpha1),
-        preceded(tag(" has flow rate="), digit1),
+        preceded(tag(" has flow rate="), unsigned),
     ))(line)?;
     let (line, valves) = preceded(
         alt((
             tag("; tunnels lead to valves "),
             tag("; tunnel leads to valve "),
         )),
-        many1(terminated(alpha1, opt(tag(", ")))),
+        comma_list(alpha1),
     )(line)?;
     let (line, _) = opt(newline)(line)?;
     Ok((
@@ -40,83 +46,289 @@ fn parse_line(line: &str) -> IResult<&str, (Valve, Vec<&str>)> {
         (
             Valve {
                 id: id.to_string(),
-                rate: rate.parse().unwrap(),
-                state: ValveState::Closed,
+                rate,
             },
             valves,
         ),
     ))
 }
 
-fn solve_dumbly(nodes: HashMap<String, Valve>, edges: &HashMap<String, Vec<String>>) {
-    let mut nodes = nodes;
-    let mut tot_released: usize = 0;
-    let mut cur_valve = "AA".to_string();
-    for minute in 1..=30 {
-        println!("== Minute {} ==", minute);
-        let open = nodes
-            .values()
-            .filter(|v| {
-                if let ValveState::Open = v.state {
-                    true
-                } else {
-                    false
-                }
-            })
-            .collect::<Vec<_>>();
-        println!("Open: {:?}", open);
-        tot_released += open.iter().map(|v| v.rate).sum::<usize>();
-        for next in &edges[&cur_valve] {
-            if let ValveState::Closed = nodes[next].state {
-                nodes.get_mut(next).unwrap().state = ValveState::Open;
-                cur_valve = nodes[next].id.clone();
-                break;
+/// The valves worth opening (plus `AA`, where we start, at index 0) and how many minutes it takes
+/// to walk between each pair of them.
+pub struct Valves {
+    pub rates: Vec<usize>,
+    pub dists: Vec<Vec<usize>>,
+}
+
+impl Valves {
+    /// The most pressure that can be released in `time` minutes for every set of valves (by
+    /// their index in `rates`) that can be opened in that time.
+    pub fn best_per_opened(&self, time: usize) -> HashMap<BitSet, usize> {
+        let mut best = HashMap::new();
+        self.explore(0, time, BitSet::new(), 0, &mut best);
+        best
+    }
+
+    fn explore(
+        &self,
+        at: usize,
+        time_left: usize,
+        opened: BitSet,
+        released: usize,
+        best: &mut HashMap<BitSet, usize>,
+    ) {
+        let best_so_far = best.entry(opened).or_default();
+        *best_so_far = (*best_so_far).max(released);
+        for next in 1..self.rates.len() {
+            // walking there and opening it has to leave at least a minute of flow
+            let cost = self.dists[at][next] + 1;
+            if opened.contains(next) || cost >= time_left {
+                continue;
             }
+            let left = time_left - cost;
+            self.explore(
+                next,
+                left,
+                opened.with(next),
+                released + left * self.rates[next],
+                best,
+            );
         }
     }
-    println!("Got {} released", tot_released)
 }
 
-fn _solve_slightly_less_dumbly(nodes: HashMap<String, Valve>, edges: &HashMap<String, Vec<String>>) {
-    let mut nodes = nodes;
-    let mut tot_released: usize = 0;
-    let mut cur_valve = "AA".to_string();
-    for minute in 1..=30 {
-        println!("== Minute {} ==", minute);
-        let open = nodes
-            .values()
-            .filter(|v| {
-                if let ValveState::Open = v.state {
-                    true
-                } else {
-                    false
-                }
+impl Solution for Valves {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut nodes = HashMap::new();
+        let nodes_and_edges = parsers::all(DAY, input, many1(parse_line))?;
+        for (valve, _) in &nodes_and_edges {
+            nodes.insert(valve.id.clone(), valve.clone());
+        }
+        if let Some(unknown) = nodes_and_edges
+            .iter()
+            .flat_map(|(_, valve_conns)| valve_conns)
+            .find(|&&conn| !nodes.contains_key(conn))
+        {
+            return Err(ParseError::new(
+                DAY,
+                input,
+                unknown,
+                "expected a known valve",
+            ));
+        }
+        let start = nodes
+            .get("AA")
+            .ok_or_else(|| ParseError::new(DAY, input, input, "expected a valve AA to start at"))?;
+        let mut useful: Vec<&Valve> = nodes.values().filter(|v| v.rate > 0).collect();
+        useful.sort_by(|a, b| a.id.cmp(&b.id));
+        useful.insert(0, start);
+        // the valves opened so far are tracked in a bitset
+        if useful.len() > BitSet::CAPACITY {
+            return Err(ParseError::new(
+                DAY,
+                input,
+                input,
+                "expected at most 63 valves with flow",
+            ));
+        }
+        let index: HashMap<&str, usize> = nodes_and_edges
+            .iter()
+            .enumerate()
+            .map(|(i, (valve, _))| (valve.id.as_str(), i))
+            .collect();
+        let tunnels = nodes_and_edges.iter().flat_map(|(valve, valve_conns)| {
+            valve_conns
+                .iter()
+                .map(|conn| (index[valve.id.as_str()], index[conn], 1))
+        });
+        let all_dists = graph::floyd_warshall(nodes_and_edges.len(), tunnels);
+        let dists = useful
+            .iter()
+            .map(|from| {
+                let from_here = &all_dists[index[from.id.as_str()]];
+                // valves that can't be reached are as good as infinitely far away
+                useful
+                    .iter()
+                    .map(|to| from_here[index[to.id.as_str()]].unwrap_or(usize::MAX / 2))
+                    .collect()
             })
-            .collect::<Vec<_>>();
-        println!("Open: {:?}", open);
-        tot_released += open.iter().map(|v| v.rate).sum::<usize>();
-        for next in &edges[&cur_valve] {
-            if let ValveState::Closed = nodes[next].state {
-                nodes.get_mut(next).unwrap().state = ValveState::Open;
-                cur_valve = nodes[next].id.clone();
+            .collect();
+        Ok(Self {
+            rates: useful.iter().map(|v| v.rate).collect(),
+            dists,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        // opening nothing at all is always one of the ways, so there's always a best
+        let most = self
+            .best_per_opened(30)
+            .into_values()
+            .max()
+            .unwrap_or_default();
+        Ok(most.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        // we and the elephant open disjoint sets of valves, so pair up the best of each
+        let mut best: Vec<(BitSet, usize)> = self.best_per_opened(26).into_iter().collect();
+        best.sort_by_key(|&(_, released)| Reverse(released));
+        let mut most = 0;
+        for (i, &(mine, my_released)) in best.iter().enumerate() {
+            if my_released * 2 < most {
                 break;
             }
+            for &(theirs, their_released) in &best[i..] {
+                if mine.is_disjoint(&theirs) {
+                    most = most.max(my_released + their_released);
+                    // sorted, so nothing later with this one can do better
+                    break;
+                }
+            }
+        }
+        Ok(most.into())
+    }
+}
+
+impl Valves {
+    /// The most pressure that can be released in `time_left` minutes from `at` by opening some of
+    /// `closed`, trying every order they could be opened in.
+    fn most_released(&self, at: usize, time_left: usize, closed: &[usize]) -> usize {
+        closed
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &next)| {
+                let left = time_left.checked_sub(self.dists[at][next] + 1)?;
+                let mut rest = closed.to_vec();
+                rest.remove(i);
+                Some(left * self.rates[next] + self.most_released(next, left, &rest))
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Reference for Valves {
+    fn reference_part1(&self) -> Option<Answer> {
+        let valves: Vec<usize> = (1..self.rates.len()).collect();
+        Some(self.most_released(0, 30, &valves).into())
+    }
+
+    /// Tries every way of sharing the valves out between us and the elephant.
+    fn reference_part2(&self) -> Option<Answer> {
+        let valves: Vec<usize> = (1..self.rates.len()).collect();
+        (0..1u64 << valves.len())
+            .map(|mine| {
+                let (ours, theirs): (Vec<usize>, Vec<usize>) = valves
+                    .iter()
+                    .partition(|&&valve| mine & (1 << (valve - 1)) != 0);
+                self.most_released(0, 26, &ours) + self.most_released(0, 26, &theirs)
+            })
+            .max()
+            .map(Answer::from)
+    }
+}
+
+/// `size` valves joined up into one network, with a quarter of them (but no more than fifteen, as
+/// in the puzzle) worth opening.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(2);
+    // names get a letter longer once two letters run out, but we always start at AA
+    let letters = match count <= 26 * 26 {
+        true => 2,
+        false => 3,
+    };
+    let name = |mut i: usize| {
+        if i == 0 {
+            return "AA".to_string();
+        }
+        let mut name = vec![b'A'; letters];
+        for c in name.iter_mut().rev() {
+            *c += (i % 26) as u8;
+            i /= 26;
         }
+        String::from_utf8(name).unwrap()
+    };
+    let mut tunnels = vec![Vec::new(); count];
+    let mut join = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    // a tree to make sure everything's reachable, and then some shortcuts
+    for valve in 1..count {
+        join(valve, rng.below(valve));
+    }
+    for _ in 0..count / 4 {
+        join(rng.below(count), rng.below(count));
+    }
+    let mut rates = vec![0; count];
+    let mut valves: Vec<usize> = (1..count).collect();
+    rng.shuffle(&mut valves);
+    for &valve in valves.iter().take((count / 4).clamp(1, 15)) {
+        rates[valve] = 1 + rng.below(25);
     }
-    println!("Got {} released", tot_released)
+    (0..count)
+        .map(|valve| {
+            let to: Vec<String> = tunnels[valve].iter().map(|&to| name(to)).collect();
+            let leads = match to.len() {
+                1 => "tunnel leads to valve",
+                _ => "tunnels lead to valves",
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}\n",
+                name(valve),
+                rates[valve],
+                leads,
+                to.join(", ")
+            )
+        })
+        .collect()
 }
 
-pub fn main() {
-    let mut nodes = HashMap::new();
-    let mut edges: HashMap<_, Vec<_>> = HashMap::new();
-    let (rest, nodes_and_edges) = many1(parse_line)(include_str!("data/day16.txt")).unwrap();
-    println!("{}", rest);
-    assert_eq!(rest.len(), 0);
-    for (valve, valve_conns) in &nodes_and_edges {
-        nodes.insert(valve.id.clone(), valve.clone());
-        edges.insert(valve.id.clone(), valve_conns.iter().map(|e| e.to_string()).collect());
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{check_parser, whole};
+
+    fn text((valve, tunnels): &(Valve, Vec<String>)) -> String {
+        let leads = match tunnels.len() {
+            1 => "tunnel leads to valve",
+            _ => "tunnels lead to valves",
+        };
+        format!(
+            "Valve {} has flow rate={}; {} {}\n",
+            valve.id,
+            valve.rate,
+            leads,
+            tunnels.join(", ")
+        )
+    }
+
+    #[test]
+    fn test_parse_line_round_trip() {
+        const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        check_parser(
+            200,
+            "Valve hasflowrte=;0123456789 ",
+            |rng| {
+                let valve = Valve {
+                    id: rng.string(LETTERS, 2),
+                    rate: rng.below(30),
+                };
+                let tunnels = (0..1 + rng.below(5))
+                    .map(|_| rng.string(LETTERS, 2))
+                    .collect();
+                let line = (valve, tunnels);
+                let text = text(&line);
+                (line, text)
+            },
+            text,
+            |text| {
+                let (valve, tunnels) = whole(parse_line(text))?;
+                Some((valve, tunnels.iter().map(|t| t.to_string()).collect()))
+            },
+        );
     }
-    println!("{:?}", nodes);
-    println!("{:?}", edges);
-    solve_dumbly(nodes, &edges);
 }
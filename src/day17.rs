@@ -1,18 +1,39 @@
 use std::{
-    fmt::Display,
+    collections::HashSet,
     ops::{Index, IndexMut},
 };
 
+use crate::{
+    animate::{Frame, Simulation},
+    cycle,
+    error::{Error, ParseError},
+    geometry::Point,
+    grid::Grid,
+    image::{Image, Palette},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 17;
+
 const X_LEN: usize = 7;
+/// How far down [`Tower::surface`] looks. A column that stays empty (which some jet patterns
+/// never blow a rock into) would otherwise get deeper with every rock and the tower would never
+/// seem to repeat. Nothing falls anywhere near this far down a well in practice.
+const SURFACE_DEPTH: usize = 64;
 
-#[derive(Debug, Copy, Clone)]
-struct Point {
-    x: usize,
-    y: usize,
-}
+const PALETTE: Palette = Palette {
+    colours: &[
+        ('.', [15, 15, 30]),
+        ('#', [150, 150, 160]),
+        ('@', [230, 120, 30]),
+    ],
+    other: [255, 0, 255],
+};
 
-#[derive(Debug)]
-enum Rock {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Rock {
     Horizontal,
     Plus,
     L,
@@ -21,40 +42,43 @@ enum Rock {
 }
 
 impl Rock {
-    fn as_coords(&self, top_left: &Point) -> Vec<Point> {
-        #[cfg_attr(rustfmt, rustfmt_skip)]
-        match self {
-            &Rock::Horizontal => vec![
-                Point {x: top_left.x, y: top_left.y},
-                Point { x: top_left.x + 1, y: top_left.y, },
-                Point { x: top_left.x + 2, y: top_left.y, },
-                Point { x: top_left.x + 3, y: top_left.y, },
+    /// The spaces the rock covers when the bottom left corner of its bounding box is at
+    /// `bottom_left`. Higher `y` is further up the tower.
+    #[rustfmt::skip]
+    fn as_coords(&self, bottom_left: &Point<usize>) -> Vec<Point<usize>> {
+        let Point { x, y } = *bottom_left;
+        match *self {
+            Rock::Horizontal => vec![
+                Point { x, y },
+                Point { x: x + 1, y },
+                Point { x: x + 2, y },
+                Point { x: x + 3, y },
             ],
-            &Rock::Plus => vec![
-                Point { x: top_left.x + 1, y: top_left.y, },
-                Point {x: top_left.x, y: top_left.y - 1},
-                Point {x: top_left.x + 1, y: top_left.y - 1},
-                Point {x: top_left.x + 2, y: top_left.y - 1},
-                Point {x: top_left.x + 1, y: top_left.y - 2},
+            Rock::Plus => vec![
+                Point { x: x + 1, y },
+                Point { x, y: y + 1 },
+                Point { x: x + 1, y: y + 1 },
+                Point { x: x + 2, y: y + 1 },
+                Point { x: x + 1, y: y + 2 },
             ],
-            &Rock::L => vec![
-                Point{x: top_left.x + 2, y: top_left.y},
-                Point{x: top_left.x + 2, y: top_left.y - 1},
-                Point{x: top_left.x + 2, y: top_left.y - 2},
-                Point{x: top_left.x + 1, y: top_left.y - 2},
-                Point{x: top_left.x, y: top_left.y - 2},
+            Rock::L => vec![
+                Point { x, y },
+                Point { x: x + 1, y },
+                Point { x: x + 2, y },
+                Point { x: x + 2, y: y + 1 },
+                Point { x: x + 2, y: y + 2 },
             ],
-            &Rock::Vertical => vec![
-                Point {x: top_left.x, y: top_left.y},
-                Point{x: top_left.x, y: top_left.y - 1},
-                Point{x: top_left.x, y: top_left.y - 2},
-                Point{x: top_left.x, y: top_left.y - 3},
+            Rock::Vertical => vec![
+                Point { x, y },
+                Point { x, y: y + 1 },
+                Point { x, y: y + 2 },
+                Point { x, y: y + 3 },
             ],
-            &Rock::Square => vec![
-                Point {x: top_left.x, y: top_left.y},
-                Point{x: top_left.x + 1, y: top_left.y},
-                Point{x: top_left.x + 1, y: top_left.y - 1},
-                Point{x: top_left.x, y: top_left.y - 1},
+            Rock::Square => vec![
+                Point { x, y },
+                Point { x: x + 1, y },
+                Point { x, y: y + 1 },
+                Point { x: x + 1, y: y + 1 },
             ]
         }
     }
@@ -69,172 +93,250 @@ impl Rock {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 enum Space {
     Empty,
     Rock,
 }
 
-struct Grid {
-    grid: Vec<[Space; X_LEN]>,
-    cur_top_left: Point,
+struct Tower {
+    /// Row 0 is the floor. Only rows with rock in them are stored, so this is as tall as the tower.
+    spaces: Grid<Space>,
+    jets: Vec<char>,
+    next_jet: usize,
+    cur_bottom_left: Point<usize>,
     cur_rock: Rock,
-    highest_rock_y: usize,
 }
 
-impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut base = self.base_disp_grid();
-        for point in self.cur_rock.as_coords(&self.cur_top_left) {
-            (point, &base.len(), &base[0].len(), self.cur_top_left);
-            base[point.y][point.x] = '@';
+/// One push and one fall at a time, forever.
+impl Simulation for Tower {
+    fn step(&mut self) -> bool {
+        self.push_and_fall();
+        true
+    }
+
+    fn endless(&self) -> bool {
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let falling = self.cur_rock.as_coords(&self.cur_bottom_left);
+        let top = falling.iter().map(|p| p.y + 1).max().unwrap().max(self.height());
+        // the floor goes at the bottom, so the picture is upside down from the grid
+        let flip = |p: &Point<usize>| (p.x, top - 1 - p.y);
+        let mut picture = Grid::new(X_LEN, top, '.');
+        for ((x, y), space) in self.spaces.iter() {
+            if let Space::Rock = space {
+                picture[flip(&Point { x, y })] = '#';
+            }
+        }
+        for p in &falling {
+            picture[flip(p)] = '@';
+        }
+        Frame {
+            picture,
+            focus: Some(flip(&self.cur_bottom_left)),
         }
-        f.write_str(
-            base.iter()
-                .map(|col| col.iter().collect())
-                .intersperse("\n".to_string())
-                .collect::<String>()
-                .as_str(),
-        )
     }
+
+    fn palette(&self) -> Palette {
+        PALETTE
+    }
+}
+
+/// The tower once part 1's 2022 rocks have landed, with the next one on its way down.
+pub fn picture(input: &str) -> Result<Image, ParseError> {
+    let mut tower = Tower::new(Jets::parse(input)?.0);
+    for _ in 0..2022 {
+        tower.drop_rock();
+    }
+    Ok(Image::from_chars(&tower.frame().picture, &PALETTE))
+}
+
+/// Animates the rocks falling, for as long as it's left running.
+pub fn animate(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Tower::new(Jets::parse(input)?.0)))
 }
 
-impl Grid {
-    fn new() -> Self {
-        let highest_y = 0;
+impl Tower {
+    fn new(jets: Vec<char>) -> Self {
         Self {
-            grid: Vec::default(),
-            cur_top_left: Point { x: 2, y: 3 },
+            spaces: Grid::new(X_LEN, 0, Space::Empty),
+            jets,
+            next_jet: 0,
+            cur_bottom_left: Point { x: 2, y: 3 },
             cur_rock: Rock::Horizontal,
-            highest_rock_y: highest_y,
         }
     }
-    fn base_disp_grid(&self) -> Vec<Vec<char>> {
-        dbg!(self.highest_rock_y, self.cur_top_left);
-        let after = vec![
-            vec!['.'; X_LEN];
-            (self.highest_rock_y.max(self.cur_top_left.y) - self.grid.len()) + 1
-        ];
-        let mut base: Vec<Vec<char>> = self
-            .grid
+    fn height(&self) -> usize {
+        self.spaces.height()
+    }
+    fn fits(&self, bottom_left: &Point<usize>) -> bool {
+        self.cur_rock
+            .as_coords(bottom_left)
             .iter()
-            .rev()
-            .map(|col| {
-                col.iter().map(|elem| match elem {
-                    Space::Empty => '.',
-                    Space::Rock => '#',
-                }).collect()
-            })
-            .collect();
-        base.extend(after);
-        base
-    }
-    fn lock_in(&mut self, point: &Point) {
-        let mut new_h_y = self.highest_rock_y;
-        for space in self.cur_rock.as_coords(point) {
+            .all(|&space| space.x < X_LEN && self[space] == Space::Empty)
+    }
+    fn lock_in(&mut self) {
+        for space in self.cur_rock.as_coords(&self.cur_bottom_left) {
             self[space] = Space::Rock;
-            new_h_y = new_h_y.max(space.y)
         }
-        self.highest_rock_y = new_h_y;
-    }
-    fn run(&mut self) {
-        let winddiriter = include_str!("data/day17.txt")
-            .strip_suffix("\n")
-            .unwrap()
-            .chars()
-            .cycle();
-        let (left, right) = (0, X_LEN);
-        for wind in winddiriter {
-            println!("{}", self);
-            println!("-------------------------");
-            // move with the wind
-            let poses = self.cur_rock.as_coords(&self.cur_top_left);
-            match wind {
-                '>' if !poses.iter().any(|&elem| (elem.x + 1) >= right) => self.cur_top_left.x += 1,
-                '<' if !poses.iter().any(|&elem| (elem.x - 1) < left) => self.cur_top_left.x -= 1,
-                '<' | '>' => {}
-                _ => unreachable!(),
+        self.cur_rock = self.cur_rock.next();
+        // new rocks start two in from the left wall and three above the tallest rock
+        self.cur_bottom_left = Point {
+            x: 2,
+            y: self.height() + 3,
+        };
+    }
+    /// Lets the current rock fall until it comes to rest.
+    fn drop_rock(&mut self) {
+        while !self.push_and_fall() {}
+    }
+    /// Blows the current rock along with the next jet, then lets it fall one. Gives whether it
+    /// came to rest instead.
+    fn push_and_fall(&mut self) -> bool {
+        // move with the wind
+        let wind = self.jets[self.next_jet];
+        self.next_jet = (self.next_jet + 1) % self.jets.len();
+        let Point { x, y } = self.cur_bottom_left;
+        let pushed = match wind {
+            '>' => Some(Point { x: x + 1, y }),
+            '<' if x > 0 => Some(Point { x: x - 1, y }),
+            '<' => None,
+            _ => unreachable!(),
+        };
+        if let Some(pushed) = pushed.filter(|p| self.fits(p)) {
+            self.cur_bottom_left = pushed;
+        }
+        // falllllllllllllllllllllllllllllllllllllll
+        let Point { x, y } = self.cur_bottom_left;
+        if y == 0 || !self.fits(&Point { x, y: y - 1 }) {
+            self.lock_in();
+            return true;
+        }
+        self.cur_bottom_left = Point { x, y: y - 1 };
+        false
+    }
+    /// Every empty space a rock could still get into from above, as its column and how far down
+    /// from the top of the tower it is, up to [`SURFACE_DEPTH`]. Rocks only move left, right and
+    /// down, so nothing else can ever change, and two towers with the same surface carry on the
+    /// same way. (Just the highest rock in each column isn't enough: rocks can slide in under an
+    /// overhang.)
+    fn surface(&self) -> Vec<(usize, usize)> {
+        let height = self.height();
+        let deepest = SURFACE_DEPTH.min(height);
+        let mut seen = HashSet::new();
+        // depth 0 is the row just above the tower, which is open all the way across
+        let mut todo: Vec<(usize, usize)> = (0..X_LEN).map(|x| (x, 0)).collect();
+        while let Some((x, depth)) = todo.pop() {
+            if !seen.insert((x, depth)) {
+                continue;
             }
-            dbg!(self.cur_top_left);
-            // falllllllllllllllllllllllllllllllllllllll
-            let mut locked = false;
-            if self.cur_top_left.y == 0 {
-                let top_left = self.cur_top_left;
-                self.lock_in(&top_left);
-                locked = true;
-            } else {
-                for space in self.cur_rock.as_coords(&Point {
-                    x: self.cur_top_left.x,
-                    y: self.cur_top_left.y - 1,
-                }) {
-                    if let Space::Rock = &self[space] {
-                        let top_left = self.cur_top_left;
-                        self.lock_in(&top_left);
-                        locked = true;
-                        break;
-                    }
-                }
+            let mut next = vec![(x, depth + 1)];
+            if x > 0 {
+                next.push((x - 1, depth));
             }
-            if !locked {
-                self.cur_top_left = (Point {
-                    x: self.cur_top_left.x,
-                    y: self.cur_top_left.y - 1,
-                });
-            } else {
-                self.cur_rock = (self.cur_rock.next());
-                self.cur_top_left = Point {
-                    x: 2,
-                    y: self.highest_rock_y
-                        + match self.cur_rock {
-                            Rock::Vertical => 4,
-                            Rock::L | Rock::Plus => 3,
-                            Rock::Square => 2,
-                            Rock::Horizontal => 1,
-                        },
-                };
+            if x + 1 < X_LEN {
+                next.push((x + 1, depth));
+            }
+            for (x, depth) in next {
+                if depth == 0
+                    || (depth <= deepest && self.spaces[(x, height - depth)] == Space::Empty)
+                {
+                    todo.push((x, depth));
+                }
             }
         }
+        let mut surface: Vec<_> = seen.into_iter().collect();
+        surface.sort();
+        surface
     }
 }
 
-impl Index<Point> for Grid {
+impl Index<Point<usize>> for Tower {
     type Output = Space;
 
-    fn index(&self, index: Point) -> &Self::Output {
-        // higher means TALLER, but we store rows the opposite. So we need to translate that.
-        let highest_y = self.highest_rock_y.max(self.cur_top_left.y);
-        if highest_y <= index.y {
+    fn index(&self, index: Point<usize>) -> &Self::Output {
+        if self.height() <= index.y {
             // if someone is asking higher than we know, it's definitely empty.
             &Space::Empty
         } else {
-            // else, we want to figure out how high from the top it should be
-            &self.grid[index.y][index.x]
+            &self.spaces[index.into()]
         }
     }
 }
 
-impl IndexMut<Point> for Grid {
-    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
-        let highest_y = self.highest_rock_y.max(self.cur_top_left.y);
-        if highest_y <= index.y {
-            self.grid
-                .append(&mut vec![[Space::Empty; X_LEN]; index.y - highest_y + 1]);
+impl IndexMut<Point<usize>> for Tower {
+    fn index_mut(&mut self, index: Point<usize>) -> &mut Self::Output {
+        if self.height() <= index.y {
+            self.spaces.resize_height(index.y + 1, Space::Empty);
         };
-        &mut self.grid[index.y][index.x]
+        &mut self.spaces[index.into()]
+    }
+}
+
+pub struct Jets(pub Vec<char>);
+
+impl Jets {
+    /// How tall the tower is once `rocks` rocks have come to rest.
+    pub fn height_after(&self, rocks: usize) -> usize {
+        let mut heights = vec![0];
+        // once the falling rock, the jets and the shape of the top of the tower all line up with
+        // an earlier rock, everything after repeats
+        let cycle = cycle::find(
+            &mut Tower::new(self.0.clone()),
+            |tower| (tower.cur_rock, tower.next_jet, tower.surface()),
+            |tower| {
+                tower.drop_rock();
+                heights.push(tower.height());
+            },
+            rocks,
+        );
+        match cycle {
+            Some(cycle) => cycle.extrapolate(rocks, |dropped| heights[dropped]),
+            None => heights[rocks],
+        }
+    }
+}
+
+impl Solution for Jets {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let jets = input.trim_end();
+        if let Some(at) = jets.find(|c| c != '<' && c != '>') {
+            return Err(ParseError::new(DAY, input, &jets[at..], "expected < or >"));
+        }
+        if jets.is_empty() {
+            return Err(ParseError::new(DAY, input, jets, "expected a jet"));
+        }
+        Ok(Self(jets.chars().collect()))
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        Ok(self.height_after(2022).into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Ok(self.height_after(1000000000000).into())
+    }
+}
+
+impl Reference for Jets {
+    /// Drops every one of the rocks, without skipping ahead.
+    fn reference_part1(&self) -> Option<Answer> {
+        let mut tower = Tower::new(self.0.clone());
+        for _ in 0..2022 {
+            tower.drop_rock();
+        }
+        Some(tower.height().into())
+    }
+
+    /// A trillion rocks are too many to drop one at a time.
+    fn reference_part2(&self) -> Option<Answer> {
+        None
     }
 }
 
-pub fn main() {
-    let rocks = vec![
-        Rock::Horizontal,
-        Rock::Plus,
-        Rock::L,
-        Rock::Vertical,
-        Rock::Square,
-    ];
-    let mut rockiter = rocks.iter().cycle();
-    let mut cur_top_left = Point { x: 0, y: 0 };
-    let cur_rock = rockiter.next().unwrap();
-    let mut grid = Grid::new();
-    grid.run();
+/// A pattern of `size` jets.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    rng.string("<>", size.max(1)) + "\n"
 }
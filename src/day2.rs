@@ -1,95 +1,177 @@
-#[derive(Debug, Clone, Copy)]
-enum Move {
+use crate::{
+    error::{Error, ParseError, Unexpected},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Move {
     Rock,
     Paper,
     Scissors,
 }
 
 impl Move {
-    fn score(&self) -> i32 {
+    pub fn score(&self) -> i32 {
         match self {
             Self::Rock => 1,
             Self::Paper => 2,
             Self::Scissors => 3,
         }
     }
+    pub fn beats(&self) -> Self {
+        match self {
+            Self::Rock => Self::Scissors,
+            Self::Paper => Self::Rock,
+            Self::Scissors => Self::Paper,
+        }
+    }
+    /// How `theirs` does when played against this move.
+    pub fn outcome_against(&self, theirs: Self) -> Outcome {
+        if theirs == *self {
+            Outcome::Draw
+        } else if theirs.beats() == *self {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
 }
 
-impl TryFrom<char> for Move {
+impl TryFrom<&str> for Move {
     type Error = &'static str;
 
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            'A' => Ok(Move::Rock),
-            'B' => Ok(Move::Paper),
-            'C' => Ok(Move::Scissors),
-            _ => Err("Oh no failed for {value}"),
+            "A" => Ok(Move::Rock),
+            "B" => Ok(Move::Paper),
+            "C" => Ok(Move::Scissors),
+            _ => Err("A, B or C"),
         }
     }
 }
 
 #[derive(Debug)]
-enum Outcome {
+pub enum Outcome {
     Win,
     Lose,
     Draw,
 }
 
-impl Outcome {}
+impl Outcome {
+    pub fn score(&self) -> i32 {
+        match self {
+            Self::Lose => 0,
+            Self::Draw => 3,
+            Self::Win => 6,
+        }
+    }
+    /// Part one reads the second column as a move rather than an outcome.
+    pub fn as_move(&self) -> Move {
+        match self {
+            Self::Lose => Move::Rock,
+            Self::Draw => Move::Paper,
+            Self::Win => Move::Scissors,
+        }
+    }
+}
 
-impl TryFrom<char> for Outcome {
+impl TryFrom<&str> for Outcome {
     type Error = &'static str;
 
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            'X' => Ok(Outcome::Lose),
-            'Y' => Ok(Outcome::Draw),
-            'Z' => Ok(Outcome::Win),
-            _ => Err("Oh no failed for {value}"),
+            "X" => Ok(Outcome::Lose),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            _ => Err("X, Y or Z"),
         }
     }
 }
 
 #[derive(Debug)]
-#[allow(dead_code)]
-struct Round {
-    opponent_move: Move,
-    desired_outcome: Outcome,
+pub struct Round {
+    pub opponent_move: Move,
+    pub desired_outcome: Outcome,
 }
 
-impl TryFrom<&str> for Round {
-    type Error = &'static str;
+impl<'a> TryFrom<&'a str> for Round {
+    type Error = Unexpected<'a>;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let (theirs, outcome) = value
+            .split_once(' ')
+            .ok_or(Unexpected::new(value, "two columns separated by a space"))?;
         Ok(Self {
-            opponent_move: Move::try_from(value.chars().nth(0).unwrap())?,
-            desired_outcome: Outcome::try_from(value.chars().nth(2).unwrap())?,
+            opponent_move: Move::try_from(theirs).map_err(|e| Unexpected::new(theirs, e))?,
+            desired_outcome: Outcome::try_from(outcome).map_err(|e| Unexpected::new(outcome, e))?,
         })
     }
 }
 
-pub fn main() {
-    let rounds: Vec<Round> = include_str!("data/day2.txt")
-        .lines()
-        .filter_map(|item| Round::try_from(item).ok())
-        .collect();
-    let mut tot_score = 0;
-    for round in rounds.iter() {
-        #[cfg_attr(rustfmt, rustfmt_skip)]
-        {
-            tot_score += match round {
-                // Wins
-                Round { opponent_move: Move::Rock, desired_outcome: Outcome::Win, } => Move::Paper.score() + 6,
-                Round { opponent_move: Move::Paper, desired_outcome: Outcome::Win, } => Move::Scissors.score() + 6,
-                Round { opponent_move: Move::Scissors, desired_outcome: Outcome::Win, } => Move::Rock.score() + 6,
-                // Tie is always just the same move
-                Round { opponent_move, desired_outcome: Outcome::Draw, } => opponent_move.score() + 3,
-                // Losses
-                Round { opponent_move: Move::Rock, desired_outcome: Outcome::Lose, } => Move::Scissors.score() + 0,
-                Round { opponent_move: Move::Paper, desired_outcome: Outcome::Lose, } => Move::Rock.score() + 0,
-                Round { opponent_move: Move::Scissors, desired_outcome: Outcome::Lose, } => Move::Paper.score() + 0,
-            };
+pub struct Strategy {
+    pub rounds: Vec<Round>,
+}
+
+impl Solution for Strategy {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            rounds: input
+                .lines()
+                .map(|item| {
+                    Round::try_from(item).map_err(|e| ParseError::unexpected(DAY, input, e))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let score: i32 = self
+            .rounds
+            .iter()
+            .map(|round| {
+                let my_move = round.desired_outcome.as_move();
+                my_move.score() + round.opponent_move.outcome_against(my_move).score()
+            })
+            .sum();
+        Ok(score.into())
+    }
+
+    #[rustfmt::skip]
+    fn part2(&self) -> Result<Answer, Error> {
+        let mut tot_score = 0;
+        for round in self.rounds.iter() {
+            #[allow(clippy::identity_op)]
+            {
+                tot_score += match round {
+                    // Wins
+                    Round { opponent_move: Move::Rock, desired_outcome: Outcome::Win, } => Move::Paper.score() + 6,
+                    Round { opponent_move: Move::Paper, desired_outcome: Outcome::Win, } => Move::Scissors.score() + 6,
+                    Round { opponent_move: Move::Scissors, desired_outcome: Outcome::Win, } => Move::Rock.score() + 6,
+                    // Tie is always just the same move
+                    Round { opponent_move, desired_outcome: Outcome::Draw, } => opponent_move.score() + 3,
+                    // Losses
+                    Round { opponent_move: Move::Rock, desired_outcome: Outcome::Lose, } => Move::Scissors.score() + 0,
+                    Round { opponent_move: Move::Paper, desired_outcome: Outcome::Lose, } => Move::Rock.score() + 0,
+                    Round { opponent_move: Move::Scissors, desired_outcome: Outcome::Lose, } => Move::Paper.score() + 0,
+                };
+            }
         }
+        Ok(tot_score.into())
     }
-    println!("Score: {}", tot_score);
+}
+
+/// `size` rounds of the strategy guide.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&["A", "B", "C"]),
+                rng.pick(&["X", "Y", "Z"])
+            )
+        })
+        .collect()
 }
@@ -1,35 +1,48 @@
-use std::collections::HashSet;
+use crate::{
+    bitset::BitSet,
+    error::{Error, ParseError, Unexpected},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
 
+const DAY: u8 = 3;
+
+/// Each compartment holds the priorities of the items in it.
 #[derive(Debug)]
 pub struct Rucksack {
-    front: HashSet<char>,
-    back: HashSet<char>,
+    pub front: BitSet,
+    pub back: BitSet,
 }
 
 impl Rucksack {
-    pub fn overlaps(&self) -> HashSet<&char> {
-        self.front.intersection(&self.back).collect()
+    pub fn overlaps(&self) -> BitSet {
+        self.front & self.back
     }
-    fn items(&self) -> HashSet<&char> {
-        self.front.union(&self.back).collect()
+    pub fn items(&self) -> BitSet {
+        self.front | self.back
     }
 }
 
-impl TryFrom<&str> for Rucksack {
-    type Error = &'static str;
+impl<'a> TryFrom<&'a str> for Rucksack {
+    type Error = Unexpected<'a>;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.len() % 2 != 0 {
-            return Err("Invalid length for input -- must be even");
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        if let Some(at) = value.find(|c: char| !c.is_ascii_alphabetic()) {
+            return Err(Unexpected::new(&value[at..], "an item (a letter)"));
+        }
+        if !value.len().is_multiple_of(2) {
+            return Err(Unexpected::new(value, "an even number of items"));
         }
+        let (front, back) = value.split_at(value.len() / 2);
+        let priorities = |items: &str| items.chars().map(|c| c.prio() as usize).collect();
         Ok(Rucksack {
-            front: value[..value.len() / 2].chars().collect(),
-            back: value[value.len() / 2..].chars().collect(),
+            front: priorities(front),
+            back: priorities(back),
         })
     }
 }
 
-trait Prio {
+pub trait Prio {
     fn prio(&self) -> u32;
 }
 
@@ -44,21 +57,62 @@ impl Prio for char {
     }
 }
 
-pub fn main() {
-    let binding = include_str!("data/day3.txt")
-        .lines()
-        .filter_map(|item| Rucksack::try_from(item).ok())
-        .collect::<Vec<_>>();
-    let sacks: Vec<Vec<_>> = binding.chunks(3).map(|c| c.iter().collect()).collect();
-    let mut prio_sum = 0;
-    let inital_sack: HashSet<char> = ('a'..='z').chain('A'..='Z').collect();
-    for sack in sacks {
-        let badge = sack.iter().fold(inital_sack.clone(), |acc, r| {
-            acc.intersection(&r.items().iter().map(|c| **c).collect())
-                .map(|c| *c)
-                .collect()
-        });
-        prio_sum += badge.iter().map(|c| c.prio()).sum::<u32>();
+pub struct Rucksacks(pub Vec<Rucksack>);
+
+impl Solution for Rucksacks {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self(
+            input
+                .lines()
+                .map(|item| {
+                    Rucksack::try_from(item).map_err(|e| ParseError::unexpected(DAY, input, e))
+                })
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let priorities: usize = self.0.iter().flat_map(|sack| sack.overlaps().iter()).sum();
+        Ok(priorities.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let priorities: usize = self
+            .0
+            .chunks(3)
+            .filter_map(|group| group.iter().map(Rucksack::items).reduce(|a, b| a & b))
+            .flat_map(|badge| badge.iter())
+            .sum();
+        Ok(priorities.into())
+    }
+}
+
+/// `size` rucksacks, rounded up to whole groups of three. As in the puzzle, each rucksack has
+/// exactly one item in both compartments and each group exactly one item in all three rucksacks.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut out = String::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        rng.shuffle(&mut letters);
+        let (badge, rest) = letters.split_first().unwrap();
+        // every other item belongs to just one of the group, so the badge is all they share
+        for own in rest.chunks(rest.len().div_ceil(3)) {
+            let (shared, own) = own.split_first().unwrap();
+            let (front_items, back_items) = own.split_at(own.len() / 2);
+            let half = 2 + rng.below(15);
+            let mut front: Vec<char> = (1..half).map(|_| *rng.pick(front_items)).collect();
+            let mut back: Vec<char> = (1..half).map(|_| *rng.pick(back_items)).collect();
+            match rng.one_in(2) {
+                true => front[0] = *badge,
+                false => back[0] = *badge,
+            }
+            front.push(*shared);
+            back.push(*shared);
+            rng.shuffle(&mut front);
+            rng.shuffle(&mut back);
+            out.extend(front.into_iter().chain(back));
+            out.push('\n');
+        }
     }
-    println!("Total Prio: {}", prio_sum);
+    out
 }
@@ -1,47 +1,130 @@
 use nom::{
-    character::complete::{char, one_of},
-    multi::many1,
-    sequence::tuple,
+    character::complete::char,
+    combinator::map,
+    sequence::{separated_pair, tuple},
     IResult,
 };
-use std::{
-    collections::HashSet,
-    ops::RangeInclusive,
+use std::ops::RangeInclusive;
+
+use crate::{
+    error::{Error, ParseError},
+    interval::IntervalSet,
+    parsers::{self, unsigned},
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
 };
 
+const DAY: u8 = 4;
+
 fn parse_range(input: &str) -> IResult<&str, RangeInclusive<usize>> {
-    let (input, (start_str, _, end_str)) = tuple((
-        many1(one_of("1234567890")),
-        char('-'),
-        many1(one_of("1234567890")),
-    ))(input)?;
-    Ok((
-        input,
-        RangeInclusive::new(
-            start_str.iter().collect::<String>().parse().unwrap(),
-            end_str.iter().collect::<String>().parse().unwrap(),
-        ),
-    ))
+    map(separated_pair(unsigned, char('-'), unsigned), |(first, last)| {
+        RangeInclusive::new(first, last)
+    })(input)
 }
 
-fn parse_line(input: &str) -> IResult<&str, (RangeInclusive<usize>, RangeInclusive<usize>)> {
+pub fn parse_line(input: &str) -> IResult<&str, (RangeInclusive<usize>, RangeInclusive<usize>)> {
     let (input, (range1, _, rang2)) = tuple((parse_range, char(','), parse_range))(input)?;
     Ok((input, (range1, rang2)))
 }
 
-pub fn main() {
-    let items: Vec<(RangeInclusive<usize>, RangeInclusive<usize>)> = include_str!("data/day4.txt")
-        .lines()
-        .map(|l| parse_line(l).unwrap().1)
-        .collect();
-    let mut found = 0;
-    for (first, second) in items {
-        let first_items = first.collect::<HashSet<usize>>();
-        let second_items = second.collect::<HashSet<usize>>();
-        let res: Vec<&usize> = first_items.intersection(&second_items).collect();
-        if !res.is_empty() {
-            found += 1;
-        }
+/// The sections an elf is assigned.
+fn sections(range: &RangeInclusive<usize>) -> IntervalSet {
+    IntervalSet::from(*range.start() as i64..=*range.end() as i64)
+}
+
+pub struct Assignments(pub Vec<(RangeInclusive<usize>, RangeInclusive<usize>)>);
+
+impl Solution for Assignments {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self(parsers::each_line(DAY, input, parse_line)?))
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let contained = self
+            .0
+            .iter()
+            .filter(|(first, second)| {
+                let (first, second) = (sections(first), sections(second));
+                first.is_superset(&second) || second.is_superset(&first)
+            })
+            .count();
+        Ok(contained.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let overlapping = self
+            .0
+            .iter()
+            .filter(|(first, second)| !sections(first).intersection(&sections(second)).is_empty())
+            .count();
+        Ok(overlapping.into())
+    }
+}
+
+impl Reference for Assignments {
+    /// Checks every section of one assignment against the other.
+    fn reference_part1(&self) -> Option<Answer> {
+        let within = |a: &RangeInclusive<usize>, b: &RangeInclusive<usize>| {
+            a.clone().all(|section| b.contains(&section))
+        };
+        let contained = self.0.iter().filter(|(a, b)| within(a, b) || within(b, a));
+        Some(contained.count().into())
+    }
+
+    fn reference_part2(&self) -> Option<Answer> {
+        let overlapping = self
+            .0
+            .iter()
+            .filter(|(first, second)| first.clone().any(|section| second.contains(&section)));
+        Some(overlapping.count().into())
+    }
+}
+
+/// `size` pairs of section assignments.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut range = || {
+        let start = 1 + rng.below(99);
+        (start, start + rng.below(100 - start))
+    };
+    (0..size.max(1))
+        .map(|_| {
+            let ((a, b), (c, d)) = (range(), range());
+            format!("{}-{},{}-{}\n", a, b, c, d)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{check_parser, whole};
+
+    #[test]
+    fn test_parse_line_round_trip() {
+        let text = |(first, second): &(RangeInclusive<usize>, RangeInclusive<usize>)| {
+            format!(
+                "{}-{},{}-{}",
+                first.start(),
+                first.end(),
+                second.start(),
+                second.end()
+            )
+        };
+        check_parser(
+            200,
+            "-, x\n",
+            |rng| {
+                let mut range = || {
+                    let start = rng.below(100);
+                    start..=start + rng.below(100)
+                };
+                let pair = (range(), range());
+                let line = text(&pair);
+                (pair, line)
+            },
+            text,
+            |line| whole(parse_line(line)),
+        );
     }
-    println!("Overlaps: {}", found);
 }
@@ -1,19 +1,25 @@
-use std::str::FromStr;
-
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, digit1, newline, space1},
-    combinator::opt,
-    multi::many1,
+    combinator::{consumed, map, opt},
+    multi::{many1, separated_list1},
     sequence::{delimited, preceded, terminated, tuple},
     IResult,
 };
 
+use crate::{
+    error::{Error, ParseError},
+    parsers::{self, unsigned},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 5;
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Crate {
-    ident: String,
+    pub ident: String,
 }
 
 impl Crate {
@@ -32,28 +38,20 @@ impl Crate {
 
 #[derive(Eq, PartialEq, Debug)]
 pub struct Move {
-    how_many: usize,
-    from: usize,
-    to: usize,
-}
-
-pub fn parse_int<T: FromStr>(input: &str) -> IResult<&str, T> {
-    let (input, found) = digit1(input)?;
-    match found.parse() {
-        Ok(val) => Ok((input, val)),
-        Err(_) => todo!(),
-    }
+    pub how_many: usize,
+    pub from: usize,
+    pub to: usize,
 }
 
 impl Move {
     pub fn parse(input: &str) -> IResult<&str, Self> {
         let (input, (_, move_v, _, from, _, to)) = tuple((
             tag("move "),
-            parse_int,
+            unsigned,
             tag(" from "),
-            parse_int,
+            unsigned,
             tag(" to "),
-            parse_int,
+            unsigned,
         ))(input)?;
         Ok((
             input,
@@ -74,12 +72,21 @@ pub struct CraneGame {
 
 impl CraneGame {
     pub fn parse(input: &str) -> IResult<&str, Self> {
+        map(Self::parse_located, |(game, _)| game)(input)
+    }
+
+    /// Also gives the text of each move, to point at if it turns out not to be playable.
+    fn parse_located(input: &str) -> IResult<&str, (Self, Vec<&str>)> {
         let (input, stacks) = Self::parse_crates(input)?;
-        let (input, moves) = preceded(
+        let (input, located) = preceded(
             tuple((many1(alt((digit1, space1))), tag("\n\n"))),
-            many1(terminated(Move::parse, opt(newline))),
+            terminated(
+                separated_list1(newline, consumed(Move::parse)),
+                opt(newline),
+            ),
         )(input)?;
-        Ok((input, CraneGame { stacks, moves }))
+        let (texts, moves) = located.into_iter().unzip();
+        Ok((input, (CraneGame { stacks, moves }, texts)))
     }
 
     pub fn parse_crates(input: &str) -> IResult<&str, Vec<Vec<Crate>>> {
@@ -92,6 +99,10 @@ impl CraneGame {
             stacks.push(Vec::new());
         }
         for row in crates_by_row {
+            // the top rows may have had their trailing spaces trimmed
+            if stacks.len() < row.len() {
+                stacks.resize(row.len(), Vec::new());
+            }
             for (stack, _crate) in row.iter().enumerate() {
                 if let Some(c) = _crate {
                     stacks[stack].push(c.to_owned());
@@ -104,32 +115,126 @@ impl CraneGame {
         Ok((input, stacks))
     }
 
-    fn solve(&mut self) -> String {
+    /// Plays the moves on how many crates each stack holds, to find any that `solve` couldn't
+    /// make: to or from a stack that isn't there, taking more crates than the stack has, or leaving
+    /// a stack with no crate on top to read off. `input` is what the game was parsed from and
+    /// `texts` the part of it each move was parsed from.
+    fn check(&self, input: &str, texts: &[&str]) -> Result<(), ParseError> {
+        let mut heights: Vec<usize> = self.stacks.iter().map(Vec::len).collect();
+        // the move that last emptied each stack, to point at if it stays empty
+        let mut emptied_by = vec![input; heights.len()];
+        for (_move, &text) in self.moves.iter().zip(texts) {
+            // `move <how_many> from <from> to <to>`
+            let words: Vec<&str> = text.split(' ').collect();
+            for (stack, word) in [(_move.from, words[3]), (_move.to, words[5])] {
+                if !(1..=heights.len()).contains(&stack) {
+                    let expected = format!("expected a stack from 1 to {}", heights.len());
+                    return Err(ParseError::new(DAY, input, word, expected));
+                }
+            }
+            let (from, to) = (_move.from - 1, _move.to - 1);
+            if heights[from] < _move.how_many {
+                let message = format!("stack {} only has {} to move", _move.from, heights[from]);
+                return Err(ParseError::new(DAY, input, words[1], message));
+            }
+            heights[from] -= _move.how_many;
+            heights[to] += _move.how_many;
+            if heights[from] == 0 {
+                emptied_by[from] = text;
+            }
+        }
+        match heights.iter().position(|&height| height == 0) {
+            Some(stack) => Err(ParseError::new(
+                DAY,
+                input,
+                emptied_by[stack],
+                format!("leaves stack {} with no crate on top", stack + 1),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Plays every move and reads off the top crates. The CrateMover 9000 moves crates one at a
+    /// time, so a moved group ends up reversed; the 9001 keeps the group in order.
+    pub fn solve(&self, keeps_order: bool) -> String {
+        let mut stacks = self.stacks.clone();
         for _move in &self.moves {
             let mut tmp = Vec::new();
             for _ in 0.._move.how_many {
-                let elem = self.stacks[_move.from - 1].pop().unwrap();
+                let elem = stacks[_move.from - 1].pop().unwrap();
                 tmp.push(elem)
             }
-            tmp.reverse();
-            self.stacks[_move.to - 1].extend(tmp);
+            if keeps_order {
+                tmp.reverse();
+            }
+            stacks[_move.to - 1].extend(tmp);
         }
-        self.stacks
+        stacks
             .iter()
             .map(|s| s[s.len() - 1].ident.clone())
             .collect()
     }
 }
 
-pub fn main() {
-    let input = include_str!("data/day5.txt");
-    dbg!(CraneGame::parse(input).unwrap().1.solve());
+impl Solution for CraneGame {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (game, texts) = parsers::all(DAY, input, CraneGame::parse_located)?;
+        game.check(input, &texts)?;
+        Ok(game)
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        Ok(self.solve(false).into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Ok(self.solve(true).into())
+    }
+}
+
+/// Nine stacks and `size` moves, none of which takes a stack's last crate, so there's always a
+/// crate on top of each to read off at the end.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const STACKS: usize = 9;
+    let mut heights: Vec<usize> = (0..STACKS).map(|_| 2 + rng.below(7)).collect();
+    let tallest = *heights.iter().max().unwrap();
+    let mut out = String::new();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = heights
+            .iter()
+            .map(|&height| match level < height {
+                true => format!("[{}]", rng.string("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 1)),
+                false => "   ".to_string(),
+            })
+            .collect();
+        out += &row.join(" ");
+        out.push('\n');
+    }
+    let numbers: Vec<String> = (1..=STACKS).map(|i| format!(" {} ", i)).collect();
+    out += &numbers.join(" ");
+    out += "\n\n";
+    for _ in 0..size.max(1) {
+        // there are more crates than stacks, so some stack always has one to spare
+        let from = loop {
+            let from = rng.below(STACKS);
+            if heights[from] > 1 {
+                break from;
+            }
+        };
+        let to = (from + 1 + rng.below(STACKS - 1)) % STACKS;
+        let how_many = 1 + rng.below(heights[from] - 1);
+        heights[from] -= how_many;
+        heights[to] += how_many;
+        out += &format!("move {} from {} to {}\n", how_many, from + 1, to + 1);
+    }
+    out
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::rng::{check_parser, forall, whole, Rng};
 
     #[test]
     fn test_move_parse() {
@@ -143,6 +248,11 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_move_parse_too_large() {
+        assert!(Move::parse("move 99999999999999999999999 from 2 to 1").is_err());
+    }
+
     #[test]
     fn test_crate_parse() {
         assert_eq!(
@@ -255,4 +365,132 @@ move 1 from 1 to 2";
             }
         )
     }
+
+    /// The drawing of the stacks, the numbers under them, a blank line and the moves. `trim`
+    /// says whether to trim the spaces off the end of each row of the drawing.
+    fn text(game: &CraneGame, mut trim: impl FnMut() -> bool) -> String {
+        let height = game.stacks.iter().map(Vec::len).max().unwrap();
+        let mut text = String::new();
+        for level in (0..height).rev() {
+            let row: Vec<_> = game
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(c) => format!("[{}]", c.ident),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            let row = row.join(" ");
+            match trim() {
+                true => text += row.trim_end(),
+                false => text += &row,
+            }
+            text.push('\n');
+        }
+        let numbers: Vec<_> = (1..=game.stacks.len())
+            .map(|i| format!(" {} ", i))
+            .collect();
+        text += &numbers.join(" ");
+        text += "\n\n";
+        for m in &game.moves {
+            text += &format!("move {} from {} to {}\n", m.how_many, m.from, m.to);
+        }
+        text
+    }
+
+    fn random_game(rng: &mut Rng) -> CraneGame {
+        let stacks = (0..1 + rng.below(9))
+            .map(|_| {
+                (0..1 + rng.below(5))
+                    .map(|_| Crate {
+                        ident: rng.string("ABCDEFGHIJKLMNOPQRSTUVWXYZ", 1),
+                    })
+                    .collect()
+            })
+            .collect();
+        let moves = (0..1 + rng.below(10))
+            .map(|_| Move {
+                how_many: 1 + rng.below(30),
+                from: 1 + rng.below(9),
+                to: 1 + rng.below(9),
+            })
+            .collect();
+        CraneGame { stacks, moves }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        check_parser(
+            200,
+            "[] 0123456789\n",
+            |rng| {
+                let game = random_game(rng);
+                // editors like to trim the spaces off the ends of the upper rows
+                let text = text(&game, || rng.one_in(2));
+                (game, text)
+            },
+            |game| text(game, || false),
+            |text| whole(CraneGame::parse(text)),
+        );
+    }
+
+    #[test]
+    fn test_impossible_moves() {
+        let stacks = "[A]    \n[B] [C]\n 1   2 \n\n";
+        let error = |moves: &str| {
+            let input = format!("{}{}", stacks, moves);
+            let e = <CraneGame as Solution>::parse(&input).unwrap_err();
+            (e.line, e.column, e.message)
+        };
+        let at = |line, column, message: &str| (line, column, message.to_string());
+        assert_eq!(
+            error("move 1 from 0 to 2\n"),
+            at(5, 13, "expected a stack from 1 to 2")
+        );
+        assert_eq!(
+            error("move 1 from 1 to 2\nmove 1 from 2 to 3\n"),
+            at(6, 18, "expected a stack from 1 to 2")
+        );
+        assert_eq!(
+            error("move 1 from 2 to 1\nmove 3 from 2 to 1\n"),
+            at(6, 6, "stack 2 only has 0 to move")
+        );
+        assert_eq!(
+            error("move 2 from 1 to 2\nmove 1 from 2 to 2\n"),
+            at(5, 1, "leaves stack 1 with no crate on top")
+        );
+        assert_eq!(
+            error("move 1 from 2 to 1move 1 from 2 to 1\nmove 9 from 2 to 1\n"),
+            at(5, 19, "unexpected text")
+        );
+        assert!(<CraneGame as Solution>::parse(&format!("{}move 1 from 1 to 2\n", stacks)).is_ok());
+    }
+
+    #[test]
+    fn test_move_round_trip() {
+        let text = |m: &Move| format!("move {} from {} to {}", m.how_many, m.from, m.to);
+        check_parser(
+            200,
+            "mov efrt0123456789",
+            |rng| {
+                let m = Move {
+                    how_many: rng.below(50),
+                    from: rng.below(10),
+                    to: rng.below(10),
+                };
+                let line = text(&m);
+                (m, line)
+            },
+            text,
+            |line| whole(Move::parse(line)),
+        );
+    }
+
+    #[test]
+    fn test_garbage() {
+        forall(200, |rng| {
+            let len = rng.below(20);
+            assert!(Crate::parse(&rng.string("[]0123456789", len)).is_err());
+        });
+    }
 }
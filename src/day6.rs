@@ -1,19 +1,83 @@
 use std::collections::{VecDeque, HashSet};
 
-pub fn main() {
-    let input = include_str!("data/day6.txt");
-    let mut last_four = VecDeque::new();
-    for (i, char) in input.chars().enumerate() {
-        if last_four.len() != 14 {
-            last_four.push_back(char);
-            continue;
+use crate::{
+    error::{Error, ParseError},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 6;
+
+pub struct Datastream(pub String);
+
+impl Datastream {
+    /// Index just past the first run of `len` distinct characters.
+    pub fn marker(&self, len: usize) -> Option<usize> {
+        let mut last_n = VecDeque::new();
+        for (i, char) in self.0.chars().enumerate() {
+            if last_n.len() == len {
+                last_n.pop_front();
+            }
+            last_n.push_back(char);
+            let collected = last_n.iter().collect::<HashSet<&char>>();
+            if collected.len() == len {
+                return Some(i + 1);
+            }
         }
-        last_four.pop_front();
-        last_four.push_back(char);
-        let collected = last_four.iter().collect::<HashSet<&char>>();
-        if collected.len() == 14 {
-            println!("Index: {}", i+1);
-            break;
+        None
+    }
+}
+
+impl Solution for Datastream {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let stream = input.trim_end();
+        if let Some(at) = stream.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(
+                DAY,
+                input,
+                &stream[at..],
+                "expected a lowercase letter",
+            ));
         }
+        Ok(Self(stream.to_string()))
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let marker = self
+            .marker(4)
+            .ok_or(Error::NoAnswer("there's no start-of-packet marker"))?;
+        Ok(marker.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let marker = self
+            .marker(14)
+            .ok_or(Error::NoAnswer("there's no start-of-message marker"))?;
+        Ok(marker.into())
+    }
+}
+
+/// `size` characters, where the only start-of-message marker is right at the end.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // thirteen letters can't make fourteen different ones in a row
+    let mut stream = rng.string("abcdefghijklm", size.saturating_sub(14));
+    let mut marker: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut marker);
+    stream.extend(&marker[..14]);
+    stream + "\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_only_letters() {
+        let error = |input| Datastream::parse(input).map(|_| ()).unwrap_err();
+        let e = error("abcd\nefgh\n");
+        assert_eq!((e.line, e.column), (1, 5));
+        assert_eq!(e.message, "expected a lowercase letter");
+        assert_eq!(error("abcdE").column, 5);
+        assert_eq!(Datastream::parse("abcd\n").unwrap().0, "abcd");
     }
 }
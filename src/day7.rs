@@ -4,42 +4,147 @@ use std::{
     path::PathBuf,
 };
 
-pub fn main() {
-    let mut pwd = PathBuf::new();
-    let mut files: HashMap<PathBuf, usize> = Default::default();
-    let mut dirs: HashSet<PathBuf> = Default::default();
-
-    include_str!("data/day7.txt").lines().for_each(|line| {
-        match line.split(" ").collect::<Vec<_>>()[..] {
-            ["$", "cd", dir] => {
-                pwd.push(dir);
-                pwd = pwd.absolutize().unwrap().into_owned()
-            }
-            ["$", "ls"] => (),
-            ["dir", dir] => {
-                dirs.insert({
+use crate::{
+    error::{Error, ParseError},
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 7;
+
+const DISK_SIZE: usize = 70000000;
+const NEEDED_SPACE: usize = 30000000;
+
+pub struct FileSystem {
+    pub files: HashMap<PathBuf, usize>,
+    pub dirs: HashSet<PathBuf>,
+}
+
+impl FileSystem {
+    pub fn dir_size(&self, dir: &PathBuf) -> usize {
+        self.files
+            .iter()
+            .filter_map(|(f, s)| if f.starts_with(dir) { Some(s) } else { None })
+            .sum()
+    }
+}
+
+impl Solution for FileSystem {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut pwd = PathBuf::new();
+        let mut files: HashMap<PathBuf, usize> = Default::default();
+        let mut dirs: HashSet<PathBuf> = Default::default();
+
+        for line in input.lines() {
+            match line.split(" ").collect::<Vec<_>>()[..] {
+                ["$", "cd", dir] => {
+                    pwd.push(dir);
+                    pwd = pwd
+                        .absolutize()
+                        .map_err(|e| ParseError::new(DAY, input, dir, e.to_string()))?
+                        .into_owned();
+                    dirs.insert(pwd.clone());
+                }
+                ["$", "ls"] => (),
+                ["dir", dir] => {
+                    dirs.insert({
+                        let mut new_p = pwd.clone();
+                        new_p.push(dir);
+                        new_p
+                    });
+                }
+                [size, fname] => {
                     let mut new_p = pwd.clone();
-                    new_p.push(dir);
-                    new_p
-                });
+                    new_p.push(fname);
+                    let size = size
+                        .parse()
+                        .map_err(|_| ParseError::new(DAY, input, size, "expected a file size"))?;
+                    files.insert(new_p, size);
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        DAY,
+                        input,
+                        line,
+                        "expected `$ cd <dir>`, `$ ls`, `dir <name>` or `<size> <name>`",
+                    ))
+                }
             }
-            [size, fname] => {
-                let mut new_p = pwd.clone();
-                new_p.push(fname);
-                files.insert(new_p, size.parse().unwrap());
+        }
+        Ok(Self { files, dirs })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        let mut tot_size = 0;
+        for dir in &self.dirs {
+            let size = self.dir_size(dir);
+            if size <= 100000 {
+                tot_size += size;
             }
-            _ => unimplemented!(),
         }
-    });
-    let mut tot_size = 0;
-    for dir in dirs {
-        let size: usize = files
+        Ok(tot_size.into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        let used: usize = self.files.values().sum();
+        let free = DISK_SIZE
+            .checked_sub(used)
+            .ok_or(Error::NoAnswer("the files don't fit on the disk"))?;
+        let to_free = NEEDED_SPACE.saturating_sub(free);
+        let smallest = self
+            .dirs
             .iter()
-            .filter_map(|(f, s)| if f.starts_with(&dir) { Some(s) } else { None })
-            .sum();
-        if size <= 100000 {
-            tot_size += size;
+            .map(|dir| self.dir_size(dir))
+            .filter(|&size| size >= to_free)
+            .min()
+            .ok_or(Error::NoAnswer("no directory frees up enough space"))?;
+        Ok(smallest.into())
+    }
+}
+
+/// A terminal session exploring a filesystem of about `size` files, never more than ten
+/// directories deep and small enough to fit on the disk.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let biggest = (60_000_000 / size.max(1)).clamp(1, 300_000);
+    let per_dir = 1 + size / 1000;
+    let mut out = vec!["$ cd /".to_string()];
+    // the directories still to go into, at each level down from the root
+    let mut to_visit: Vec<Vec<String>> = Vec::new();
+    let (mut files, mut dirs) = (0, 0);
+    loop {
+        out.push("$ ls".to_string());
+        let subdirs = match files < size && to_visit.len() < 10 {
+            true => 2 + rng.below(2),
+            false => 0,
+        };
+        let names: Vec<String> = (0..subdirs)
+            .map(|_| {
+                dirs += 1;
+                format!("d{}", dirs)
+            })
+            .collect();
+        out.extend(names.iter().map(|name| format!("dir {}", name)));
+        for _ in 0..per_dir + rng.below(3) {
+            files += 1;
+            out.push(format!("{} f{}.txt", 1 + rng.below(biggest), files));
+        }
+        to_visit.push(names);
+        loop {
+            let Some(level) = to_visit.last_mut() else {
+                return out.join("\n") + "\n";
+            };
+            match level.pop() {
+                Some(name) => {
+                    out.push(format!("$ cd {}", name));
+                    break;
+                }
+                None => {
+                    to_visit.pop();
+                    if !to_visit.is_empty() {
+                        out.push("$ cd ..".to_string());
+                    }
+                }
+            }
         }
     }
-    dbg!(tot_size);
 }
@@ -1,30 +1,39 @@
+use crate::{
+    error::{Error, ParseError},
+    geometry::{Direction, Point},
+    grid::Grid,
+    image::Image,
+    reference::Reference,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 8;
+
 #[derive(Debug)]
-struct Grid {
-    grid: Vec<Vec<u32>>,
+pub struct Forest {
+    pub trees: Grid<u32>,
 }
 
-impl Grid {
-    fn visible(&self, x: usize, y: usize) -> bool {
-        let row = &self.grid[y];
-        let col: Vec<_> = self.grid.iter().map(|line| line[x]).collect();
+impl Forest {
+    pub fn visible(&self, x: usize, y: usize) -> bool {
+        let row = self.trees.row(y);
+        let col: Vec<_> = self.trees.column(x).copied().collect();
         row[..x].iter().all(|&elem| elem < row[x])
             || row[x + 1..].iter().all(|&elem| elem < row[x])
             || col[..y].iter().all(|&elem| elem < col[y])
             || col[y + 1..].iter().all(|&elem| elem < col[y])
     }
-    fn count_visible(&self) -> usize {
-        (0..self.grid.len())
-            .map(|y| {
-                (0..self.grid[y].len())
-                    .filter(|x| self.visible(*x, y))
-                    .count()
-            })
-            .sum()
+    pub fn count_visible(&self) -> usize {
+        self.trees
+            .positions()
+            .filter(|&(x, y)| self.visible(x, y))
+            .count()
     }
-    fn senic_score(&self, x: usize, y: usize) -> usize {
-        let row = &self.grid[y];
-        let col: Vec<_> = self.grid.iter().map(|line| line[x]).collect();
-        let consider = self.grid[y][x];
+    pub fn senic_score(&self, x: usize, y: usize) -> usize {
+        let row = self.trees.row(y);
+        let col: Vec<_> = self.trees.column(x).copied().collect();
+        let consider = self.trees[(x, y)];
         let mut score = 1;
         // note: do not like
         // It's important that we do 1..x and x+1..row.len()-1
@@ -53,46 +62,100 @@ impl Grid {
             + 1;
         score
     }
-    fn most_senic_score(&self) -> usize {
-        (1..self.grid.len() - 1)
-            .map(|y| {
-                (1..self.grid[y].len() - 1)
-                    .map(|x| self.senic_score(x, y))
-                    .max()
-                    .unwrap()
-            })
+    /// Trees on the edge see nothing one way and score 0, so a forest that's all edge scores 0.
+    pub fn most_senic_score(&self) -> usize {
+        (1..self.trees.height().saturating_sub(1))
+            .flat_map(|y| (1..self.trees.width().saturating_sub(1)).map(move |x| (x, y)))
+            .map(|(x, y)| self.senic_score(x, y))
             .max()
-            .unwrap()
+            .unwrap_or_default()
     }
 }
 
-pub fn main() {
-    let grid = Grid {
-        grid: include_str!("data/day8.txt")
-            .lines()
-            .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-            .collect(),
-    };
-    println!("Visible: {}", grid.count_visible());
-    println!("Most Senic: {}", grid.most_senic_score());
+/// Tree heights in grey, the taller the lighter.
+pub fn picture(input: &str) -> Result<Image, ParseError> {
+    let forest = Forest::parse(input)?;
+    Ok(Image::Grey(
+        forest.trees.map(|_, &height| (height * 255 / 9) as u8),
+    ))
+}
+
+impl Solution for Forest {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let trees = Grid::parse(input, "a tree height (0-9)", |c| c.to_digit(10))
+            .map_err(|e| ParseError::unexpected(DAY, input, e))?;
+        if trees.width() == 0 || trees.height() == 0 {
+            return Err(ParseError::new(
+                DAY,
+                input,
+                input,
+                "expected at least one tree",
+            ));
+        }
+        Ok(Forest { trees })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        Ok(self.count_visible().into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Ok(self.most_senic_score().into())
+    }
+}
+
+impl Reference for Forest {
+    /// `count_visible` already looks along every line from every tree.
+    fn reference_part1(&self) -> Option<Answer> {
+        None
+    }
+
+    /// Walks out from every tree, the ones on the edge too, until something blocks the view.
+    fn reference_part2(&self) -> Option<Answer> {
+        let score = |(x, y)| {
+            let height = self.trees[(x, y)];
+            Direction::ALL
+                .iter()
+                .map(|&dir| {
+                    let mut at = Point::new(x, y);
+                    let mut seen = 0;
+                    while let Some(next) = at.checked_step(dir) {
+                        let Some(&tree) = self.trees.get((next.x, next.y)) else {
+                            break;
+                        };
+                        seen += 1;
+                        if tree >= height {
+                            break;
+                        }
+                        at = next;
+                    }
+                    seen
+                })
+                .product::<usize>()
+        };
+        self.trees.positions().map(score).max().map(Answer::from)
+    }
+}
+
+/// A `size` by `size` forest, with at least one tree that isn't on the edge.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    (0..size)
+        .map(|_| rng.string("0123456789", size) + "\n")
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use super::Grid;
+    use super::{Forest, Solution};
 
     const GRID: &str = "30373
 25512
 65332
 33549
 35390";
-    fn parse() -> Grid {
-        Grid {
-            grid: GRID
-                .lines()
-                .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-                .collect(),
-        }
+    fn parse() -> Forest {
+        Forest::parse(GRID).unwrap()
     }
 
     #[test]
@@ -130,5 +193,7 @@ mod test {
     fn test_most_senic_score() {
         let grid = parse();
         assert_eq!(8, grid.most_senic_score());
+        // all edge, so nothing can see anything
+        assert_eq!(0, Forest::parse("12\n34").unwrap().most_senic_score());
     }
 }
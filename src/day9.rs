@@ -1,209 +1,203 @@
-use std::{collections::HashSet, thread::sleep, time::Duration};
+use std::collections::HashSet;
+
+use crate::{
+    animate::{Frame, Simulation},
+    error::{Error, ParseError, Unexpected},
+    geometry::{Bounds, Direction, Point},
+    grid::Grid,
+    image::Palette,
+    rng::Rng,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 9;
+
+const PALETTE: Palette = Palette {
+    colours: &[
+        ('.', [245, 245, 235]),
+        ('#', [120, 170, 220]),
+        ('H', [200, 30, 30]),
+    ],
+    other: [240, 140, 40],
+};
+
+/// Where the knots are. `y` grows downwards, so up is negative.
+pub type Coord = Point<i32>;
 
 #[derive(Debug, Clone, Copy)]
-enum Step {
-    Up(usize),
-    Down(usize),
-    Left(usize),
-    Right(usize),
+pub struct Step {
+    pub dir: Direction,
+    pub count: usize,
 }
 
-impl From<&str> for Step {
-    fn from(line: &str) -> Self {
-        match line.split(" ").collect::<Vec<_>>()[..] {
-            ["U", how_many] => Self::Up(how_many.parse().unwrap()),
-            ["D", how_many] => Self::Down(how_many.parse().unwrap()),
-            ["L", how_many] => Self::Left(how_many.parse().unwrap()),
-            ["R", how_many] => Self::Right(how_many.parse().unwrap()),
-            _ => unreachable!(),
-        }
-    }
-}
+impl<'a> TryFrom<&'a str> for Step {
+    type Error = Unexpected<'a>;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
-struct Coord {
-    x: i32,
-    y: i32,
+    fn try_from(line: &'a str) -> Result<Self, Self::Error> {
+        let (dir, how_many) = line
+            .split_once(' ')
+            .ok_or(Unexpected::new(line, "a direction and a distance"))?;
+        let how_many = how_many
+            .parse()
+            .map_err(|_| Unexpected::new(how_many, "a distance"))?;
+        let dir = match dir {
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            _ => return Err(Unexpected::new(dir, "U, D, L or R")),
+        };
+        Ok(Self {
+            dir,
+            count: how_many,
+        })
+    }
 }
 
-impl Coord {
-    fn dist(&self, other: &Self) -> usize {
-        (self.x - other.x).abs().max((self.y - other.y).abs()) as usize
-    }
-    fn move_closer(self, other: &Self) -> Self {
-        let dist = self.dist(other);
-        if dist <= 1 {
-            self
-        } else {
-            let x = self.x;
-            let y = self.y;
-            let mut best_move = self;
-            for x_move in if x == other.x { 0..1 } else { -1..2 } {
-                for y_move in if y == other.y { 0..1 } else { -1..2 } {
-                    let test = Self {
-                        x: x + x_move,
-                        y: y + y_move,
-                    };
-                    let test_dist = test.dist(other);
-                    let best_move_dist = best_move.dist(other);
-                    if test_dist < best_move_dist {
-                        best_move = test;
-                    }
-                    if test_dist == best_move_dist && (test.x == other.x || test.y == other.y) {
-                        best_move = test;
-                    }
+/// Where `knot` goes to keep up with `other`, which it has to touch, diagonals included.
+pub fn move_closer(knot: Coord, other: &Coord) -> Coord {
+    let dist = knot.chebyshev(other);
+    if dist <= 1 {
+        knot
+    } else {
+        let x = knot.x;
+        let y = knot.y;
+        let mut best_move = knot;
+        for x_move in if x == other.x { 0..1 } else { -1..2 } {
+            for y_move in if y == other.y { 0..1 } else { -1..2 } {
+                let test = Coord::new(x + x_move, y + y_move);
+                let test_dist = test.chebyshev(other);
+                let best_move_dist = best_move.chebyshev(other);
+                if test_dist < best_move_dist {
+                    best_move = test;
+                }
+                if test_dist == best_move_dist && (test.x == other.x || test.y == other.y) {
+                    best_move = test;
                 }
             }
-            best_move
         }
+        best_move
     }
 }
 
 #[derive(Debug)]
-struct Rope {
-    steps: Vec<Step>,
+pub struct Rope {
+    pub steps: Vec<Step>,
     head: Coord,
     knots: Vec<Coord>,
     visited: Vec<Coord>,
+    /// The step being taken, and how many moves of it have been made so far.
+    next: (usize, usize),
 }
 
-impl From<&str> for Rope {
-    fn from(input: &str) -> Self {
+impl Rope {
+    /// A rope with `knots` knots following the head.
+    pub fn new(steps: Vec<Step>, knots: usize) -> Self {
         Self {
-            steps: input.lines().map(|l| l.into()).collect(),
+            steps,
             head: Coord { x: 0, y: 0 },
-            knots: vec![Coord { x: 0, y: 0 }; 9],
+            knots: vec![Coord { x: 0, y: 0 }; knots],
             visited: vec![Coord { x: 0, y: 0 }],
+            next: (0, 0),
+        }
+    }
+    fn run(&mut self) {
+        while Simulation::step(self) {}
+    }
+    /// How many places the last of `knots` knots visits.
+    pub fn tail_visits(&self, knots: usize) -> usize {
+        let mut rope = Rope::new(self.steps.clone(), knots);
+        rope.run();
+        let uniq: HashSet<_> = rope.visited.iter().collect();
+        uniq.len()
+    }
+    fn move_head(&mut self, dir: Direction) {
+        self.head = self.head.step(dir);
+        let new_knot = move_closer(self.knots[0], &self.head);
+        self.knots[0] = new_knot;
+        //self.visited.push(self.knots[0]);
+        for elem in 1..self.knots.len() {
+            let new_knot = move_closer(self.knots[elem], &self.knots[elem - 1]);
+            self.knots[elem] = new_knot;
+            //self.visited.push(self.knots[elem]);
         }
+        self.visited.push(self.knots[self.knots.len() - 1]);
     }
 }
 
-impl Rope {
-    fn run(&mut self, steps: &mut Vec<Step>) {
-        for step in steps.iter_mut() {
-            self.step(&step);
-            self.draw();
+/// One move of the head at a time.
+impl Simulation for Rope {
+    fn step(&mut self) -> bool {
+        // move on past finished steps, and any that never had a move to make
+        while self.steps.get(self.next.0).is_some_and(|step| self.next.1 >= step.count) {
+            self.next = (self.next.0 + 1, 0);
         }
+        let Some(step) = self.steps.get(self.next.0) else {
+            return false;
+        };
+        self.move_head(step.dir);
+        self.next.1 += 1;
+        true
     }
-    fn draw(&self) {
-        let mut visited: HashSet<_> = self.visited.iter().collect();
-        visited.insert(&self.head);
-        visited.extend(&self.knots);
-        let min_x = visited.iter().map(|c| c.x).min().unwrap();
-        let max_x = visited.iter().map(|c| c.x).max().unwrap();
-        let min_y = visited.iter().map(|c| c.y).min().unwrap();
-        let max_y = visited.iter().map(|c| c.y).max().unwrap();
-        let mut out: Vec<String> = Vec::new();
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                let c = Coord { x, y };
-                if c == self.head {
-                    out.push("H".into());
-                } else if let Some(f) =
-                    self.knots
-                        .iter()
-                        .enumerate()
-                        .find_map(|(i, e)| if e == &c { Some(i) } else { None })
-                {
-                    out.push((f + 1).to_string());
-                } else if visited.contains(&c) {
-                    out.push("#".into());
-                } else {
-                    out.push(".".into());
-                }
-            }
-            out.push("\n".into());
+
+    fn frame(&self) -> Frame {
+        let knots = self.knots.iter().chain([&self.head]);
+        let bounds = Bounds::of(self.visited.iter().chain(knots).copied()).unwrap();
+        let pos = |c: &Coord| ((c.x - bounds.min.x) as usize, (c.y - bounds.min.y) as usize);
+        let mut picture = Grid::new(bounds.width() as usize, bounds.height() as usize, '.');
+        for c in &self.visited {
+            picture[pos(c)] = '#';
         }
-        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
-        let out = out[..(65*252).min(out.len())].join("");
-        print!("{}", out);
-        sleep(Duration::from_millis(20));
-    }
-    fn step(&mut self, step: &Step) {
-        match step {
-            Step::Up(count) => {
-                for _ in 0..*count {
-                    self.head = Coord {
-                        x: self.head.x,
-                        y: self.head.y - 1,
-                    };
-                    let new_knot = self.knots[0].move_closer(&self.head);
-                    self.knots[0] = new_knot;
-                    //self.visited.push(self.knots[0]);
-                    for elem in 1..self.knots.len() {
-                        let new_knot = self.knots[elem].move_closer(&self.knots[elem - 1]);
-                        self.knots[elem] = new_knot;
-                    }
-                    self.visited.push(self.knots[8]);
-                    // self.draw();
-                }
-            }
-            Step::Down(count) => {
-                for _ in 0..*count {
-                    self.head = Coord {
-                        x: self.head.x,
-                        y: self.head.y + 1,
-                    };
-                    let new_knot = self.knots[0].move_closer(&self.head);
-                    self.knots[0] = new_knot;
-                    //self.visited.push(self.knots[0]);
-                    for elem in 1..self.knots.len() {
-                        let new_knot = self.knots[elem].move_closer(&self.knots[elem - 1]);
-                        self.knots[elem] = new_knot;
-                        //self.visited.push(self.knots[elem]);
-                    }
-                    self.visited.push(self.knots[8]);
-                    // self.draw();
-                }
-            }
-            Step::Left(count) => {
-                for _ in 0..*count {
-                    self.head = Coord {
-                        x: self.head.x - 1,
-                        y: self.head.y,
-                    };
-                    let new_knot = self.knots[0].move_closer(&self.head);
-                    self.knots[0] = new_knot;
-                    //self.visited.push(self.knots[0]);
-                    for elem in 1..self.knots.len() {
-                        let new_knot = self.knots[elem].move_closer(&self.knots[elem - 1]);
-                        self.knots[elem] = new_knot;
-                        //self.visited.push(self.knots[elem]);
-                    }
-                    self.visited.push(self.knots[8]);
-                    // self.draw();
-                }
-            }
-            Step::Right(count) => {
-                for _ in 0..*count {
-                    self.head = Coord {
-                        x: self.head.x + 1,
-                        y: self.head.y,
-                    };
-                    let new_knot = self.knots[0].move_closer(&self.head);
-                    self.knots[0] = new_knot;
-                    //self.visited.push(self.knots[0]);
-                    for elem in 1..self.knots.len() {
-                        let new_knot = self.knots[elem].move_closer(&self.knots[elem - 1]);
-                        self.knots[elem] = new_knot;
-                        //self.visited.push(self.knots[elem]);
-                    }
-                    self.visited.push(self.knots[8]);
-                    // self.draw();
-                }
-            }
+        // the knots further along go underneath the ones before them
+        for (i, c) in self.knots.iter().enumerate().rev() {
+            picture[pos(c)] = char::from_digit(i as u32 + 1, 36).unwrap_or('*');
         }
+        picture[pos(&self.head)] = 'H';
+        Frame {
+            picture,
+            focus: Some(pos(&self.head)),
+        }
+    }
+
+    fn palette(&self) -> Palette {
+        PALETTE
+    }
+}
+
+/// Animates the longer rope.
+pub fn animate(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Rope::parse(input)?))
+}
+
+impl Solution for Rope {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let steps = input
+            .lines()
+            .map(|l| Step::try_from(l).map_err(|e| ParseError::unexpected(DAY, input, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(steps, 9))
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        Ok(self.tail_visits(1).into())
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Ok(self.tail_visits(9).into())
     }
 }
 
-pub fn main() {
-    let mut rope: Rope = include_str!("data/day9.txt").into();
-    let mut steps = Vec::new();
-    steps.clone_from(&rope.steps);
-    rope.run(&mut steps);
-    rope.draw();
-    let uniq: HashSet<_> = rope.visited.iter().collect();
-    println!("uniq: {}", uniq.len());
+/// `size` moves of the head.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&["U", "D", "L", "R"]),
+                1 + rng.below(20)
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -213,15 +207,15 @@ mod test {
     fn test_move_closer() {
         assert_eq!(
             Coord { x: 1, y: 1 },
-            Coord { x: 0, y: 0 }.move_closer(&Coord { x: 2, y: 2 })
+            move_closer(Coord { x: 0, y: 0 }, &Coord { x: 2, y: 2 })
         );
         assert_eq!(
             Coord { x: 0, y: 1 },
-            Coord { x: 0, y: 0 }.move_closer(&Coord { x: 0, y: 2 })
+            move_closer(Coord { x: 0, y: 0 }, &Coord { x: 0, y: 2 })
         );
         assert_eq!(
             Coord { x: 1, y: 1 },
-            Coord { x: 1, y: 1 }.move_closer(&Coord { x: 2, y: 2 })
+            move_closer(Coord { x: 1, y: 1 }, &Coord { x: 2, y: 2 })
         );
     }
 }
@@ -0,0 +1,249 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use nom::error::ErrorKind;
+
+use crate::{input::InputError, scaffold::ScaffoldError};
+
+/// Why a puzzle input could not be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based, like an editor shows it.
+    pub line: usize,
+    /// 1-based, counted in characters.
+    pub column: usize,
+    /// The whole line the problem is on.
+    pub text: String,
+    pub message: String,
+}
+
+/// A problem found by a parser that only sees part of the input, before it's known which day or
+/// input it came from. `at` must be a slice of the input so it can be located later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unexpected<'a> {
+    pub at: &'a str,
+    pub expected: &'static str,
+}
+
+impl<'a> Unexpected<'a> {
+    pub fn new(at: &'a str, expected: &'static str) -> Self {
+        Self { at, expected }
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "expected a number".to_string(),
+        ErrorKind::Alpha => "expected letters".to_string(),
+        ErrorKind::Space => "expected a space".to_string(),
+        ErrorKind::CrLf => "expected a new line".to_string(),
+        ErrorKind::Eof => "expected the end of the input".to_string(),
+        ErrorKind::MapRes => "number out of range".to_string(),
+        ErrorKind::TooLarge => "nested too deep".to_string(),
+        ErrorKind::Char | ErrorKind::Tag => "unexpected text".to_string(),
+        kind => format!("unexpected text ({})", kind.description()),
+    }
+}
+
+impl ParseError {
+    /// `at` has to be a slice of `input` (which is what nom and `str::lines` hand out), as its
+    /// position in `input` is what gives the line and column.
+    pub fn new(day: u8, input: &str, at: &str, message: impl Into<String>) -> Self {
+        let mut offset = (at.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            day,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end].to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn unexpected(day: u8, input: &str, unexpected: Unexpected) -> Self {
+        Self::new(
+            day,
+            input,
+            unexpected.at,
+            format!("expected {}", unexpected.expected),
+        )
+    }
+
+    pub fn from_nom(day: u8, input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => {
+                Self::new(day, input, &input[input.len()..], "unexpected end of input")
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Self::new(day, input, e.input, describe(e.code))
+            }
+        }
+    }
+
+    /// For parsers that stopped before the end; anything left over other than whitespace is an
+    /// error.
+    pub fn check_rest(day: u8, input: &str, rest: &str) -> Result<(), Self> {
+        match rest.trim() {
+            "" => Ok(()),
+            rest => Err(Self::new(day, input, rest, "unexpected text")),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "day {} input, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        writeln!(f, "    {}", self.text)?;
+        write!(f, "    {}^", " ".repeat(self.column - 1))
+    }
+}
+
+/// Problems with the files the runner keeps for itself, like known answers or bench baselines.
+#[derive(Debug)]
+pub enum FileError {
+    Io {
+        path: PathBuf,
+        err: io::Error,
+    },
+    Malformed {
+        path: PathBuf,
+        line: usize,
+        text: String,
+        expected: &'static str,
+    },
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, err } => write!(f, "{}: {}", path.display(), err),
+            Self::Malformed {
+                path,
+                line,
+                text,
+                expected,
+            } => write!(
+                f,
+                "{} line {}: expected `{}`, got `{}`",
+                path.display(),
+                line,
+                expected,
+                text
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Unimplemented(u8),
+    NotAnimated(u8),
+    NoPicture(u8),
+    Input(InputError),
+    Parse(ParseError),
+    File(FileError),
+    Scaffold(ScaffoldError),
+    /// Saving every frame of a simulation that never stops would never finish; `output` is the
+    /// option that asked to.
+    Endless {
+        day: u8,
+        output: &'static str,
+    },
+    /// The input parsed, but a part can't come to an answer from it, like a map with no way
+    /// across. Says why.
+    NoAnswer(&'static str),
+    /// Puzzles only have parts 1 and 2.
+    NoPart(u8),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unimplemented(day) => write!(f, "day {} is not implemented", day),
+            Self::NotAnimated(day) => write!(f, "day {} has no animation", day),
+            Self::NoPicture(day) => write!(f, "day {} has no picture", day),
+            Self::Input(e) => e.fmt(f),
+            Self::Parse(e) => e.fmt(f),
+            Self::File(e) => e.fmt(f),
+            Self::Scaffold(e) => e.fmt(f),
+            Self::Endless { day, output } => write!(
+                f,
+                "day {} goes on forever, so {} needs --steps",
+                day, output
+            ),
+            Self::NoAnswer(why) => f.write_str(why),
+            Self::NoPart(part) => write!(f, "there is no part {}", part),
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<FileError> for Error {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+impl From<ScaffoldError> for Error {
+    fn from(e: ScaffoldError) -> Self {
+        Self::Scaffold(e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_locates_slice() {
+        let input = "first line\nsecond line\nthird";
+        let err = ParseError::new(4, input, &input[18..22], "bad");
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 8);
+        assert_eq!(err.text, "second line");
+        assert_eq!(
+            err.to_string(),
+            "day 4 input, line 2, column 8: bad\n    second line\n           ^"
+        );
+    }
+
+    #[test]
+    fn test_locates_end_of_input() {
+        let input = "abc\n";
+        let err = ParseError::new(1, input, &input[4..], "more please");
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "");
+    }
+
+    #[test]
+    fn test_check_rest() {
+        let input = "123\n\n";
+        assert_eq!(ParseError::check_rest(1, input, &input[3..]), Ok(()));
+        let input = "123\n\n  abc\n";
+        let err = ParseError::check_rest(1, input, &input[3..]).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+}
@@ -0,0 +1,232 @@
+//! The worked examples from each day's puzzle, with the answers the puzzle gives for them, so
+//! every day can be checked without anyone's private input. The inputs live in `src/examples`,
+//! named the way [`verify`](crate::verify) names its variants.
+
+use crate::{
+    day15,
+    error::ParseError,
+    find_day,
+    solution::{Parser, Solution},
+};
+
+pub struct Example {
+    pub day: u8,
+    /// `None` for `day<N>.txt`, otherwise the `<variant>` of `day<N>.<variant>.txt`.
+    pub variant: Option<&'static str>,
+    pub input: &'static str,
+    /// What the puzzle says each part comes to, `None` where it doesn't say for this input.
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+    /// How to parse the input when the puzzle asks something different of the example than of
+    /// the real input. The day's usual parser otherwise.
+    pub parse: Option<Parser>,
+}
+
+impl Example {
+    pub fn parser(&self) -> Parser {
+        self.parse
+            .unwrap_or_else(|| find_day(self.day).expect("examples are only given for real days"))
+    }
+
+    /// `dayN` or `dayN.variant`.
+    pub fn name(&self) -> String {
+        match self.variant {
+            None => format!("day{}", self.day),
+            Some(variant) => format!("day{}.{}", self.day, variant),
+        }
+    }
+}
+
+/// The example asks about row 10 and a beacon between 0 and 20, where the real input asks about
+/// row 2000000 and a beacon between 0 and 4000000.
+fn parse_day15(input: &str) -> Result<Box<dyn Solution>, ParseError> {
+    let sensors = day15::Sensors::parse(input)?;
+    Ok(Box::new(day15::Sensors {
+        row_to_test: 10,
+        max: 20,
+        ..sensors
+    }))
+}
+
+const DAY10_PART2: &str = concat!(
+    "##..##..##..##..##..##..##..##..##..##..\n",
+    "###...###...###...###...###...###...###.\n",
+    "####....####....####....####....####....\n",
+    "#####.....#####.....#####.....#####.....\n",
+    "######......######......######......####\n",
+    "#######.......#######.......#######....."
+);
+
+macro_rules! example {
+    ($day:literal, $part1:expr, $part2:expr) => {
+        example!($day, $part1, $part2, None)
+    };
+    ($day:literal, $part1:expr, $part2:expr, $parse:expr) => {
+        Example {
+            day: $day,
+            variant: None,
+            input: include_str!(concat!("examples/day", $day, ".txt")),
+            part1: Some($part1),
+            part2: Some($part2),
+            parse: $parse,
+        }
+    };
+}
+
+pub const EXAMPLES: &[Example] = &[
+    example!(1, "24000", "45000"),
+    example!(2, "15", "12"),
+    example!(3, "157", "70"),
+    example!(4, "2", "4"),
+    example!(5, "CMZ", "MCD"),
+    example!(6, "7", "19"),
+    example!(7, "95437", "24933642"),
+    example!(8, "21", "8"),
+    example!(9, "13", "1"),
+    Example {
+        day: 9,
+        variant: Some("larger"),
+        input: include_str!("examples/day9.larger.txt"),
+        part1: Some("88"),
+        part2: Some("36"),
+        parse: None,
+    },
+    example!(10, "13140", DAY10_PART2),
+    example!(11, "10605", "2713310158"),
+    example!(12, "31", "29"),
+    example!(13, "13", "140"),
+    example!(14, "24", "93"),
+    example!(15, "26", "56000011", Some(parse_day15 as Parser)),
+    example!(16, "1651", "1707"),
+    example!(17, "3068", "1514285714288"),
+];
+
+/// Runs the example through the day's solution and describes every part that doesn't come out
+/// as the puzzle says.
+pub fn check(example: &Example) -> Vec<String> {
+    let solution = match example.parser()(example.input) {
+        Ok(solution) => solution,
+        Err(e) => return vec![format!("{}: {}", example.name(), e)],
+    };
+    [(1, example.part1), (2, example.part2)]
+        .into_iter()
+        .filter_map(|(part, expected)| {
+            let expected = expected?;
+            let answer = match solution.part(part) {
+                Ok(answer) => answer.to_string(),
+                Err(e) => return Some(format!("{} part {}: {}", example.name(), part, e)),
+            };
+            (answer != expected).then(|| {
+                format!(
+                    "{} part {}: expected {}, got {}",
+                    example.name(),
+                    part,
+                    expected,
+                    answer
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::{forall, mutate};
+    use crate::DAYS;
+
+    #[test]
+    fn test_examples() {
+        let wrong: Vec<_> = EXAMPLES.iter().flat_map(check).collect();
+        assert!(wrong.is_empty(), "\n{}", wrong.join("\n"));
+    }
+
+    #[test]
+    fn test_every_day_has_an_example() {
+        for day in DAYS {
+            assert!(
+                EXAMPLES.iter().any(|example| example.day == day.number),
+                "day {} has no example",
+                day.number
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let broken = Example {
+            input: "not a calorie count",
+            ..example!(1, "24000", "45000")
+        };
+        assert_eq!(check(&broken).len(), 1);
+        let wrong = example!(1, "1", "45000");
+        assert_eq!(
+            check(&wrong),
+            vec!["day1 part 1: expected 1, got 24000".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_no_answer_is_reported() {
+        // each parses, but leaves a part with nothing to answer
+        let unanswerable = [
+            Example {
+                input: "",
+                part2: None,
+                ..example!(1, "24000", "45000")
+            },
+            Example {
+                input: "abcabc",
+                ..example!(6, "7", "19")
+            },
+            Example {
+                input: "$ cd /\n80000000 big\n",
+                part1: None,
+                ..example!(7, "95437", "24933642")
+            },
+            Example {
+                input: "SbcdE",
+                ..example!(12, "31", "29")
+            },
+            Example {
+                input: "Sensor at x=10, y=10: closest beacon is at x=30, y=10\n",
+                part1: None,
+                ..example!(15, "26", "56000011", Some(parse_day15 as Parser))
+            },
+        ];
+        let reported: Vec<_> = unanswerable.iter().flat_map(check).collect();
+        assert_eq!(
+            reported,
+            vec![
+                "day1 part 1: no elves",
+                "day6 part 1: there's no start-of-packet marker",
+                "day6 part 2: there's no start-of-message marker",
+                "day7 part 2: the files don't fit on the disk",
+                "day12 part 1: there's no way up from S to E",
+                "day12 part 2: there's no way up to E from elevation a",
+                "day15 part 2: there's no spot for the distress beacon",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_garbage_is_an_error() {
+        forall(50, |rng| {
+            for day in DAYS {
+                let len = rng.below(50);
+                // no day's input starts with a `~`
+                let garbage = format!("~{}", rng.string("0123456789abcxyz -=+,:;[]#.\n\t→", len));
+                assert!(
+                    (day.parse)(&garbage).is_err(),
+                    "day {} took {:?}",
+                    day.number,
+                    garbage
+                );
+            }
+            for example in EXAMPLES {
+                // could come out as valid as it started, but mustn't panic
+                let _ = example.parser()(&mutate(rng, example.input));
+            }
+        });
+    }
+}
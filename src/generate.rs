@@ -0,0 +1,52 @@
+//! Made-up puzzle inputs, for stress testing and benchmarking the days on bigger inputs than the
+//! real ones. Each day's generator turns a seed and a size into an input the day can solve; what
+//! the size counts (lines, elves, valves, ...) is up to the day, but bigger is always bigger.
+
+use crate::rng::Rng;
+
+/// Makes an input of roughly `size` things from `rng`.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// The size `generate` and `bench` use when not told otherwise.
+pub const DEFAULT_SIZE: usize = 100;
+/// The seed `generate` and `bench` use when not told otherwise.
+pub const DEFAULT_SEED: u64 = 2022;
+
+/// The input `generator` makes from `seed`, always the same one for the same seed and size.
+pub fn generate(generator: Generator, size: usize, seed: u64) -> String {
+    generator(&mut Rng::new(seed), size)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pool, DAYS};
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for day in DAYS {
+            let Some(generator) = day.generate else {
+                continue;
+            };
+            let (day, parse) = (day.number, day.parse);
+            for (seed, size) in [(0, 1), (1, 5), (2, 30), (3, 30)] {
+                let input = generate(generator, size, seed);
+                assert_eq!(input, generate(generator, size, seed), "day {}", day);
+                let solution = parse(&input).unwrap_or_else(|e| {
+                    panic!("day {} size {} seed {}: {}\n{}", day, size, seed, e, input)
+                });
+                for part in [1, 2] {
+                    let message = match pool::catch(|| solution.part(part)) {
+                        Ok(Ok(_)) => continue,
+                        Ok(Err(e)) => e.to_string(),
+                        Err(message) => message,
+                    };
+                    panic!(
+                        "day {} part {} size {} seed {}: {}\n{}",
+                        day, part, size, seed, message, input
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Sub, SubAssign},
+};
+
+/// The integer types a [`Point`] can be made of.
+pub trait Coordinate:
+    Copy + Debug + Ord + Hash + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// How far apart two values are, which is never negative.
+    fn distance(self, other: Self) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! coordinate {
+    (signed: $($t:ty),*) => {$(
+        impl Coordinate for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                (self - other).abs()
+            }
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+        }
+    )*};
+    (unsigned: $($t:ty),*) => {$(
+        impl Coordinate for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+        }
+    )*};
+}
+
+coordinate!(signed: i32, i64, isize);
+coordinate!(unsigned: u32, u64, usize);
+
+/// The four ways along a grid. Up is towards smaller `y`, the way the puzzles draw their maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise from up.
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coordinate> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+    /// Taxicab distance: how many steps apart without moving diagonally.
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+    /// Chessboard distance: how many steps apart when diagonals count as one.
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+    /// One step `dir`wards. Panics (in debug builds) if that goes below zero on an unsigned point,
+    /// see [`Point::checked_step`].
+    pub fn step(self, dir: Direction) -> Self {
+        match dir {
+            Direction::Up => Self::new(self.x, self.y - T::ONE),
+            Direction::Right => Self::new(self.x + T::ONE, self.y),
+            Direction::Down => Self::new(self.x, self.y + T::ONE),
+            Direction::Left => Self::new(self.x - T::ONE, self.y),
+        }
+    }
+    pub fn checked_step(self, dir: Direction) -> Option<Self> {
+        Some(match dir {
+            Direction::Up => Self::new(self.x, self.y.checked_sub(T::ONE)?),
+            Direction::Right => Self::new(self.x.checked_add(T::ONE)?, self.y),
+            Direction::Down => Self::new(self.x, self.y.checked_add(T::ONE)?),
+            Direction::Left => Self::new(self.x.checked_sub(T::ONE)?, self.y),
+        })
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, by: T) -> Self {
+        Self::new(self.x * by, self.y * by)
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// The smallest rectangle holding some points, including its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Coordinate> Bounds<T> {
+    pub fn new(point: Point<T>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+    /// `None` if there are no points to bound.
+    pub fn of(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        for point in points {
+            bounds.include(point);
+        }
+        Some(bounds)
+    }
+    /// Grows to cover `point` as well.
+    pub fn include(&mut self, point: Point<T>) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, -2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.chebyshev(&b), 7);
+        let c: Point<usize> = Point::new(4, 1);
+        assert_eq!(c.manhattan(&Point::new(1, 3)), 5);
+        assert_eq!(a + b, Point::new(-2, 3));
+        assert_eq!((a - b) * 2, Point::new(8, -14));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        let origin: Point<usize> = Point::new(0, 0);
+        assert_eq!(origin.checked_step(Direction::Up), None);
+        assert_eq!(origin.step(Direction::Down), Point::new(0, 1));
+        let back = Direction::ALL
+            .iter()
+            .fold(Point::new(0, 0), |p, &dir| p.step(dir));
+        assert_eq!(back, Point::new(0, 0));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::of([Point::new(2, 3), Point::new(-1, 7), Point::new(0, 0)]).unwrap();
+        assert_eq!(bounds.min, Point::new(-1, 0));
+        assert_eq!(bounds.max, Point::new(2, 7));
+        assert_eq!((bounds.width(), bounds.height()), (4, 8));
+        assert!(bounds.contains(&Point::new(0, 5)));
+        assert!(!bounds.contains(&Point::new(3, 5)));
+        assert_eq!(Bounds::<i32>::of([]), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Shortest paths. Graphs are never built up front: the searches take a function giving each
+//! node's neighbours (and, where steps cost different amounts, what each costs), so a grid or a
+//! map of names can be searched as it is.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// What a search found: the cheapest way to every node it reached, from whichever start was
+/// closest.
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    costs: HashMap<N, C>,
+    /// The node each one was reached from, on the cheapest way there. Starts aren't in here.
+    previous: HashMap<N, N>,
+}
+
+impl<N: Hash + Eq + Clone, C: Copy> Paths<N, C> {
+    /// How much it costs to get to `node`, or `None` if there's no way there.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// Every node reached and what it cost.
+    pub fn costs(&self) -> impl Iterator<Item = (&N, &C)> {
+        self.costs.iter()
+    }
+
+    /// The way to `end` from the start it's closest to, both included.
+    pub fn path(&self, end: &N) -> Option<Vec<N>> {
+        self.costs.get(end)?;
+        let mut path = vec![end.clone()];
+        while let Some(previous) = self.previous.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `starts`, where every step costs one.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Paths<N, usize>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths {
+        costs: HashMap::new(),
+        previous: HashMap::new(),
+    };
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if paths.costs.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let steps = paths.costs[&node] + 1;
+        for next in neighbours(&node) {
+            if !paths.costs.contains_key(&next) {
+                paths.costs.insert(next.clone(), steps);
+                paths.previous.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Dijkstra's algorithm from `starts`, where `neighbours` gives each step and what it costs.
+/// Costs can't be negative.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Paths<N, C>
+where
+    N: Hash + Ord + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = Paths {
+        costs: HashMap::new(),
+        previous: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        paths.costs.insert(start.clone(), C::default());
+        queue.push(Reverse((C::default(), start)));
+    }
+    while let Some(Reverse((cost, node))) = queue.pop() {
+        // already got here a cheaper way
+        if paths.costs[&node] < cost {
+            continue;
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if paths
+                .costs
+                .get(&next)
+                .is_none_or(|&known| next_cost < known)
+            {
+                paths.costs.insert(next.clone(), next_cost);
+                paths.previous.insert(next.clone(), node.clone());
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+    paths
+}
+
+/// A* search from `start` to the first node that `is_goal`, giving what it cost and the way
+/// there. `estimate` guesses the cost to a goal, and has to never guess too high or the way
+/// found might not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    is_goal: impl Fn(&N) -> bool,
+    mut neighbours: impl FnMut(&N) -> I,
+    estimate: impl Fn(&N) -> C,
+) -> Option<(C, Vec<N>)>
+where
+    N: Hash + Ord + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = Paths {
+        costs: HashMap::from([(start.clone(), C::default())]),
+        previous: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::from([Reverse((estimate(&start), C::default(), start))]);
+    while let Some(Reverse((_, cost, node))) = queue.pop() {
+        if is_goal(&node) {
+            return Some((cost, paths.path(&node)?));
+        }
+        if paths.costs[&node] < cost {
+            continue;
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if paths
+                .costs
+                .get(&next)
+                .is_none_or(|&known| next_cost < known)
+            {
+                paths.costs.insert(next.clone(), next_cost);
+                paths.previous.insert(next.clone(), node.clone());
+                queue.push(Reverse((next_cost + estimate(&next), next_cost, next)));
+            }
+        }
+    }
+    None
+}
+
+/// The cheapest way between every pair of `count` nodes, numbered from 0, joined by `edges` of
+/// `(from, to, cost)`. `None` where there's no way at all.
+pub fn floyd_warshall<C>(
+    count: usize,
+    edges: impl IntoIterator<Item = (usize, usize, C)>,
+) -> Vec<Vec<Option<C>>>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    let mut costs = vec![vec![None; count]; count];
+    for (node, row) in costs.iter_mut().enumerate() {
+        row[node] = Some(C::default());
+    }
+    for (from, to, cost) in edges {
+        if costs[from][to].is_none_or(|known| cost < known) {
+            costs[from][to] = Some(cost);
+        }
+    }
+    for via in 0..count {
+        let onwards = costs[via].clone();
+        for row in costs.iter_mut() {
+            let Some(to_via) = row[via] else {
+                continue;
+            };
+            for (known, &from_via) in row.iter_mut().zip(&onwards) {
+                let Some(from_via) = from_via else {
+                    continue;
+                };
+                let cost = to_via + from_via;
+                if known.is_none_or(|known| cost < known) {
+                    *known = Some(cost);
+                }
+            }
+        }
+    }
+    costs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A square with a shortcut across it that's dearer than going round: 0 -1- 1 -1- 2, and
+    /// 0 -5- 2, with 3 cut off.
+    fn edges() -> Vec<(usize, usize, u32)> {
+        vec![
+            (0, 1, 1),
+            (1, 2, 1),
+            (0, 2, 5),
+            (1, 0, 1),
+            (2, 1, 1),
+            (2, 0, 5),
+        ]
+    }
+
+    fn neighbours(node: &usize) -> Vec<(usize, u32)> {
+        edges()
+            .into_iter()
+            .filter(|&(from, _, _)| from == *node)
+            .map(|(_, to, cost)| (to, cost))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        // a line 0 - 1 - 2 - ... - 9, searched from both ends
+        let line = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..10).contains(n));
+        let paths = bfs([0, 9], line);
+        assert_eq!(paths.cost(&3), Some(3));
+        assert_eq!(paths.cost(&6), Some(3));
+        assert_eq!(paths.cost(&10), None);
+        assert_eq!(paths.path(&7), Some(vec![9, 8, 7]));
+        assert_eq!(paths.path(&0), Some(vec![0]));
+        assert_eq!(paths.costs().count(), 10);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let paths = dijkstra([0], neighbours);
+        assert_eq!(paths.cost(&2), Some(2));
+        assert_eq!(paths.path(&2), Some(vec![0, 1, 2]));
+        assert_eq!(paths.cost(&3), None);
+        assert_eq!(
+            astar(0, |&n| n == 2, neighbours, |_| 0),
+            Some((2, vec![0, 1, 2]))
+        );
+        assert_eq!(astar(0, |&n| n == 3, neighbours, |_| 0), None);
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let costs = floyd_warshall(4, edges());
+        assert_eq!(costs[0][2], Some(2));
+        assert_eq!(costs[2][0], Some(2));
+        assert_eq!(costs[1][1], Some(0));
+        assert_eq!(costs[0][3], None);
+        // the same as searching from each node in turn
+        for (from, row) in costs.iter().enumerate() {
+            let paths = dijkstra([from], neighbours);
+            for (to, &cost) in row.iter().enumerate() {
+                assert_eq!(cost, paths.cost(&to));
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::ops::{Index, IndexMut};
+
+use crate::error::Unexpected;
+
+/// A position in a [`Grid`] as `(x, y)`: `x` is the column and `y` the row, both from 0.
+pub type Pos = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+#[rustfmt::skip]
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0),           (1, 0),
+    (-1, 1),  (0, 1),  (1, 1),
+];
+
+/// A rectangular grid of `T`, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Gives back the index of the first row that's a different length from the ones before it.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, usize> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(y);
+            }
+            cells.extend(row);
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Reads a map with one character per cell and one line per row. `cell` turns a character
+    /// into a cell, or refuses it by giving `None`, in which case the error says `expected`.
+    pub fn parse<'a>(
+        input: &'a str,
+        expected: &'static str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Unexpected<'a>> {
+        let mut rows = Vec::new();
+        for line in input.lines() {
+            let row = line
+                .char_indices()
+                .map(|(i, c)| cell(c).ok_or(Unexpected::new(&line[i..], expected)))
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+        Self::from_rows(rows).map_err(|y| {
+            let line = input.lines().nth(y).unwrap_or_default();
+            Unexpected::new(line, "every row to be the same length")
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        (x, y): Pos,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            self.contains(pos).then_some(pos)
+        })
+    }
+
+    /// The positions up, right, down and left of `pos` that are in the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &OFFSETS_4)
+    }
+
+    /// Like [`Grid::neighbours4`], but diagonals too.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &OFFSETS_8)
+    }
+
+    /// A grid the same shape, with `f` applied to every cell.
+    pub fn map<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+        }
+    }
+
+    /// Adds or drops rows at the end (highest `y`) so there are `height` of them.
+    pub fn resize_height(&mut self, height: usize, fill: T)
+    where
+        T: Clone,
+    {
+        self.cells.resize(height * self.width, fill);
+        self.height = height;
+    }
+
+    /// One character per cell and one line per row, the same shape [`Grid::parse`] reads.
+    pub fn render(&self, mut cell: impl FnMut(Pos, &T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for (pos, value) in self.iter() {
+            if pos.0 == 0 && pos.1 > 0 {
+                out.push('\n');
+            }
+            out.push(cell(pos, value));
+        }
+        out
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", "a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(
+            grid.render(|_, d| char::from_digit(*d, 10).unwrap()),
+            "123\n456"
+        );
+
+        let input = "12\n3x";
+        let err = Grid::parse(input, "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err, Unexpected::new(&input[4..], "a digit"));
+        let input = "12\n345";
+        let err = Grid::parse(input, "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.at, "345");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits();
+        let mut corner: Vec<_> = grid.neighbours4((0, 0)).collect();
+        corner.sort();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours4((1, 0)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_resize_height() {
+        let mut grid = Grid::new(2, 0, '.');
+        grid.resize_height(2, '.');
+        grid[(1, 1)] = '#';
+        assert_eq!(grid.render(|_, c| *c), "..\n.#");
+    }
+}
@@ -0,0 +1,149 @@
+//! Pictures of grids as PGM (greyscale) and PPM (colour) images, which need no libraries to write
+//! and which most image viewers open.
+
+use std::{fs, path::Path};
+
+use crate::{
+    animate::{self, Settings, Simulation},
+    error::{FileError, ParseError},
+    grid::Grid,
+};
+
+pub type Rgb = [u8; 3];
+
+/// Which colour each character of a picture gets.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub colours: &'static [(char, Rgb)],
+    /// For any character not listed.
+    pub other: Rgb,
+}
+
+impl Palette {
+    /// Light background, dark walls and red for anything else.
+    pub const DEFAULT: Palette = Palette {
+        colours: &[('.', [240, 240, 240]), ('#', [60, 60, 60])],
+        other: [200, 40, 40],
+    };
+
+    pub fn colour(&self, c: char) -> Rgb {
+        self.colours
+            .iter()
+            .find(|&&(known, _)| known == c)
+            .map_or(self.other, |&(_, rgb)| rgb)
+    }
+}
+
+/// One pixel per cell, until it's scaled up for saving.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Image {
+    Grey(Grid<u8>),
+    Colour(Grid<Rgb>),
+}
+
+/// Makes a day's picture from its input.
+pub type Picture = fn(&str) -> Result<Image, ParseError>;
+
+impl Image {
+    /// Colours in a character picture, like the frames of an animation.
+    pub fn from_chars(picture: &Grid<char>, palette: &Palette) -> Self {
+        Self::Colour(picture.map(|_, &c| palette.colour(c)))
+    }
+
+    /// `pgm` or `ppm`, whichever this gets saved as.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Grey(_) => "pgm",
+            Self::Colour(_) => "ppm",
+        }
+    }
+
+    /// The binary (`P5` or `P6`) form, with each cell drawn as a `scale` by `scale` square.
+    pub fn encode(&self, scale: usize) -> Vec<u8> {
+        let (magic, width, height) = match self {
+            Self::Grey(grid) => ("P5", grid.width(), grid.height()),
+            Self::Colour(grid) => ("P6", grid.width(), grid.height()),
+        };
+        let mut out =
+            format!("{}\n{} {}\n255\n", magic, width * scale, height * scale).into_bytes();
+        for y in 0..height {
+            let mut row = Vec::new();
+            for x in 0..width {
+                let pixel: &[u8] = match self {
+                    Self::Grey(grid) => std::slice::from_ref(&grid[(x, y)]),
+                    Self::Colour(grid) => &grid[(x, y)],
+                };
+                for _ in 0..scale {
+                    row.extend_from_slice(pixel);
+                }
+            }
+            for _ in 0..scale {
+                out.extend_from_slice(&row);
+            }
+        }
+        out
+    }
+
+    pub fn save(&self, path: &Path, scale: usize) -> Result<(), FileError> {
+        fs::write(path, self.encode(scale)).map_err(|err| FileError::Io {
+            path: path.to_owned(),
+            err,
+        })
+    }
+}
+
+/// Saves the frames of `simulation` that `settings` would have shown as numbered images in `dir`,
+/// `frame00000.ppm` onwards, and gives how many there were.
+pub fn sequence(
+    simulation: &mut dyn Simulation,
+    settings: &Settings,
+    dir: &Path,
+    scale: usize,
+) -> Result<usize, FileError> {
+    fs::create_dir_all(dir).map_err(|err| FileError::Io {
+        path: dir.to_owned(),
+        err,
+    })?;
+    let palette = simulation.palette();
+    let mut written = 0;
+    let mut failed = None;
+    animate::play(simulation, settings, |frame, _| {
+        let image = Image::from_chars(&frame.picture, &palette);
+        let path = dir.join(format!("frame{:05}.{}", written, image.extension()));
+        match image.save(&path, scale) {
+            Ok(()) => {
+                written += 1;
+                true
+            }
+            Err(e) => {
+                failed = Some(e);
+                false
+            }
+        }
+    });
+    match failed {
+        Some(e) => Err(e),
+        None => Ok(written),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let grey = Image::Grey(Grid::from_rows(vec![vec![0, 255]]).unwrap());
+        assert_eq!(grey.encode(1), b"P5\n2 1\n255\n\x00\xff");
+        let mut header = b"P5\n4 2\n255\n".to_vec();
+        header.extend([0, 0, 255, 255, 0, 0, 255, 255]);
+        assert_eq!(grey.encode(2), header);
+
+        let picture = Grid::parse(".#x", "anything", Some).unwrap();
+        let colour = Image::from_chars(&picture, &Palette::DEFAULT);
+        assert_eq!(colour.extension(), "ppm");
+        let mut expected = b"P6\n3 1\n255\n".to_vec();
+        expected.extend([240, 240, 240, 60, 60, 60, 200, 40, 40]);
+        assert_eq!(colour.encode(1), expected);
+    }
+}
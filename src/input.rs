@@ -0,0 +1,205 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{error::Error, generate};
+
+/// Where inputs live when nothing else is said, relative to the working directory.
+pub const DEFAULT_INPUTS_DIR: &str = "src/data";
+/// Overrides [`DEFAULT_INPUTS_DIR`] without having to pass `--inputs` every time.
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Source {
+    /// A directory holding `day1.txt`, `day2.txt`, ...
+    Dir(PathBuf),
+    /// One specific file, regardless of the day.
+    File(PathBuf),
+    Stdin,
+    /// Made up by the day's generator, see [`crate::generate`].
+    Generated {
+        size: usize,
+        seed: u64,
+    },
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { day: u8, path: PathBuf },
+    Io { path: PathBuf, err: io::Error },
+    Stdin(io::Error),
+    NoGenerator(u8),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { day, path } => write!(
+                f,
+                "no input for day {}, expected it at {} (use --inputs <dir>, {} or --input <path> to read it from elsewhere)",
+                day,
+                path.display(),
+                INPUTS_DIR_VAR,
+            ),
+            Self::Io { path, err } => write!(f, "could not read {}: {}", path.display(), err),
+            Self::Stdin(err) => write!(f, "could not read stdin: {}", err),
+            Self::NoGenerator(day) => write!(f, "day {} has no input generator", day),
+        }
+    }
+}
+
+/// The inputs directory to use when none is given on the command line.
+pub fn default_dir() -> PathBuf {
+    std::env::var_os(INPUTS_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUTS_DIR))
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Dir(default_dir())
+    }
+}
+
+impl From<&str> for Source {
+    fn from(arg: &str) -> Self {
+        match arg {
+            "-" => Self::Stdin,
+            path => Self::File(path.into()),
+        }
+    }
+}
+
+pub fn day_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.txt", day))
+}
+
+/// The variant name for the real puzzle input, `day<N>.txt`.
+pub const MAIN_VARIANT: &str = "input";
+
+/// Other inputs for a day (examples, stress tests, ...) sit next to the real one as
+/// `day<N>.<variant>.txt`.
+pub fn variant_path(dir: &Path, day: u8, variant: &str) -> PathBuf {
+    match variant {
+        MAIN_VARIANT => day_path(dir, day),
+        variant => dir.join(format!("day{}.{}.txt", day, variant)),
+    }
+}
+
+/// The variants sitting next to a day's input in `dir`, in order of name. Empty if `dir` can't
+/// be read, the same as if it had none.
+pub fn variants(dir: &Path, day: u8) -> Vec<String> {
+    let prefix = format!("day{}.", day);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut variants: Vec<String> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let variant = name.strip_prefix(&prefix)?.strip_suffix(".txt")?;
+            Some(variant.to_string())
+        })
+        .collect();
+    variants.sort();
+    variants
+}
+
+fn read_file(day: u8, path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => InputError::Missing {
+            day,
+            path: path.to_owned(),
+        },
+        _ => InputError::Io {
+            path: path.to_owned(),
+            err,
+        },
+    })
+}
+
+pub fn load_variant(dir: &Path, day: u8, variant: &str) -> Result<String, InputError> {
+    read_file(day, &variant_path(dir, day, variant))
+}
+
+impl Source {
+    /// Where a day's input comes from, for output that says which input an answer is for.
+    pub fn describe(&self, day: u8) -> String {
+        match self {
+            Self::Dir(dir) => day_path(dir, day).display().to_string(),
+            Self::File(path) => path.display().to_string(),
+            Self::Stdin => "stdin".to_string(),
+            Self::Generated { size, seed } => format!("generated (size {}, seed {})", size, seed),
+        }
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, Error> {
+        match self {
+            Self::Dir(dir) => Ok(read_file(day, &day_path(dir, day))?),
+            Self::File(path) => Ok(read_file(day, path)?),
+            Self::Stdin => {
+                let mut buf = String::new();
+                io::stdin()
+                    .read_to_string(&mut buf)
+                    .map_err(InputError::Stdin)?;
+                Ok(buf)
+            }
+            Self::Generated { size, seed } => {
+                let generator = crate::find_generator(day)?;
+                Ok(generate::generate(generator, *size, *seed))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_missing_names_path() {
+        let source = Source::Dir("does/not/exist".into());
+        let err = source.load(4).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Input(InputError::Missing { day: 4, .. })
+        ));
+        let expected = day_path(Path::new("does/not/exist"), 4);
+        assert!(err.to_string().contains(&expected.display().to_string()));
+    }
+
+    #[test]
+    fn test_variant_path() {
+        let dir = Path::new("inputs");
+        assert_eq!(variant_path(dir, 3, MAIN_VARIANT), dir.join("day3.txt"));
+        assert_eq!(variant_path(dir, 3, "example"), dir.join("day3.example.txt"));
+    }
+
+    #[test]
+    fn test_variants() {
+        let dir = std::env::temp_dir().join("aoc-2022-variants-test");
+        fs::create_dir_all(&dir).unwrap();
+        for name in "day3.txt day3.example.txt day3.big.txt day31.txt day3.md".split(' ') {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(variants(&dir, 3), vec!["big", "example"]);
+        assert_eq!(variants(&dir.join("missing"), 3), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_source_from_arg() {
+        assert_eq!(Source::from("-"), Source::Stdin);
+        assert_eq!(Source::from("a.txt"), Source::File("a.txt".into()));
+    }
+
+    #[test]
+    fn test_generated() {
+        let source = Source::Generated { size: 3, seed: 9 };
+        assert_eq!(source.load(2).unwrap().lines().count(), 3);
+        assert_eq!(source.load(2).unwrap(), source.load(2).unwrap());
+        assert_eq!(source.describe(2), "generated (size 3, seed 9)");
+        assert!(matches!(source.load(25), Err(Error::Unimplemented(25))));
+    }
+}
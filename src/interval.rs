@@ -0,0 +1,203 @@
+//! Sets of whole numbers kept as the ranges they cover rather than one by one, for when there are
+//! far too many numbers to write down but only a handful of ranges.
+
+use std::ops::RangeInclusive;
+
+/// Sorted ranges that neither overlap nor touch, so each set has only one way to be written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every number in `range`, merging it with any ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // everything from `first` up to `last` overlaps or touches the new range
+        let first = self
+            .ranges
+            .partition_point(|&(_, e)| e < start.saturating_sub(1));
+        let last = self
+            .ranges
+            .partition_point(|&(s, _)| s <= end.saturating_add(1));
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < n);
+        self.ranges.get(i).is_some_and(|&(s, _)| s <= n)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|&(s, e)| e.abs_diff(s) + 1).sum()
+    }
+
+    /// The ranges making up the set, lowest first.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+
+    /// Everything in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.ranges());
+        union
+    }
+
+    /// Everything in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut both = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                both.push((start, end));
+            }
+            // whichever finishes first can't overlap anything further on in the other
+            match a_end < b_end {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        Self { ranges: both }
+    }
+
+    /// Everything in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        match (self.ranges.first(), self.ranges.last()) {
+            (Some(&(start, _)), Some(&(_, end))) => self.intersection(&other.gaps(start..=end)),
+            _ => Self::new(),
+        }
+    }
+
+    /// Whether everything in `other` is in this set too.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    /// The numbers in `within` that aren't in the set.
+    pub fn gaps(&self, within: RangeInclusive<i64>) -> Self {
+        let (mut from, end) = within.into_inner();
+        let mut gaps = Vec::new();
+        for &(s, e) in &self.ranges {
+            if from > end || s > end {
+                break;
+            }
+            if s > from {
+                gaps.push((from, s - 1));
+            }
+            if e == i64::MAX {
+                return Self { ranges: gaps };
+            }
+            from = from.max(e + 1);
+        }
+        if from <= end {
+            gaps.push((from, end));
+        }
+        Self { ranges: gaps }
+    }
+}
+
+impl From<RangeInclusive<i64>> for IntervalSet {
+    fn from(range: RangeInclusive<i64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl Extend<RangeInclusive<i64>> for IntervalSet {
+    fn extend<T: IntoIterator<Item = RangeInclusive<i64>>>(&mut self, ranges: T) {
+        for range in ranges {
+            self.insert(range);
+        }
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<i64>>>(ranges: T) -> Self {
+        let mut set = Self::new();
+        set.extend(ranges);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::rng::{forall, Rng};
+
+    fn ranges(set: &IntervalSet) -> Vec<RangeInclusive<i64>> {
+        set.ranges().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::from_iter([1..=3, 7..=9, RangeInclusive::new(5, 4)]);
+        assert_eq!(ranges(&set), vec![1..=3, 7..=9]);
+        // touching counts as well as overlapping
+        set.insert(4..=5);
+        assert_eq!(ranges(&set), vec![1..=5, 7..=9]);
+        set.insert(0..=20);
+        assert_eq!(ranges(&set), vec![0..=20]);
+        assert_eq!(set.len(), 21);
+        assert!(set.contains(0) && set.contains(20) && !set.contains(21));
+        assert_eq!(ranges(&set.gaps(-2..=22)), vec![-2..=-1, 21..=22]);
+        assert_eq!(
+            ranges(&IntervalSet::from(i64::MIN..=0).gaps(i64::MIN..=i64::MAX)),
+            vec![1..=i64::MAX]
+        );
+    }
+
+    /// Every operation agrees with doing the same thing to the numbers one at a time.
+    #[test]
+    fn test_against_btreeset() {
+        fn random(rng: &mut Rng) -> (IntervalSet, BTreeSet<i64>) {
+            let ranges: Vec<_> = (0..rng.below(5))
+                .map(|_| {
+                    let start = rng.range(-20..=20);
+                    start..=start + rng.range(-1..=8)
+                })
+                .collect();
+            let numbers = ranges.iter().cloned().flatten().collect();
+            (ranges.into_iter().collect(), numbers)
+        }
+        fn numbers(set: &IntervalSet) -> BTreeSet<i64> {
+            set.ranges().flatten().collect()
+        }
+        forall(300, |rng| {
+            let (a, a_numbers) = random(rng);
+            let (b, b_numbers) = random(rng);
+            assert_eq!(numbers(&a), a_numbers);
+            assert_eq!(a.len(), a_numbers.len() as u64);
+            assert_eq!(numbers(&a.union(&b)), &a_numbers | &b_numbers);
+            assert_eq!(numbers(&a.intersection(&b)), &a_numbers & &b_numbers);
+            assert_eq!(numbers(&a.difference(&b)), &a_numbers - &b_numbers);
+            assert_eq!(a.is_superset(&b), a_numbers.is_superset(&b_numbers));
+            let within: BTreeSet<i64> = (-10..=10).collect();
+            assert_eq!(numbers(&a.gaps(-10..=10)), &within - &a_numbers);
+            // the same set always comes out the same, however it was put together
+            assert_eq!(a.union(&b), b.union(&a));
+        });
+    }
+}
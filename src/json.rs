@@ -0,0 +1,106 @@
+//! Answers as JSON for `run --format json`: one object per line for each day and part, for
+//! dashboards and scripts that would rather not scrape the text output.
+
+use std::{
+    fmt::{Display, Write},
+    time::Duration,
+};
+
+use crate::solution::Answer;
+
+/// `s` as a JSON string, quotes and all.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// One part's answer, or why there isn't one, and how it was got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    /// Written as `answer`, or as `error` if there's no answer.
+    pub answer: Result<Answer, String>,
+    /// Which input it's the answer for, see [`Source::describe`](crate::input::Source::describe).
+    pub input: String,
+    /// How long parsing the input took, which both parts share. `None` (written as `null`) if
+    /// parsing didn't finish, and the same for the part if it never ran.
+    pub parse_time: Option<Duration>,
+    pub part_time: Option<Duration>,
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let answer = match &self.answer {
+            Ok(Answer::Num(n)) => format!(r#""answer":{}"#, n),
+            Ok(Answer::Text(text)) => format!(r#""answer":{}"#, string(text)),
+            Err(e) => format!(r#""error":{}"#, string(e)),
+        };
+        let nanos = |time: Option<Duration>| match time {
+            Some(time) => time.as_nanos().to_string(),
+            None => "null".to_string(),
+        };
+        write!(
+            f,
+            r#"{{"day":{},"part":{},{},"input":{},"parse_ns":{},"part_ns":{}}}"#,
+            self.day,
+            self.part,
+            answer,
+            string(&self.input),
+            nanos(self.parse_time),
+            nanos(self.part_time)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let record = Record {
+            day: 10,
+            part: 2,
+            answer: Ok(Answer::Text("#.\n\"x\"\\".to_string())),
+            input: "src/data/day10.txt".to_string(),
+            parse_time: Some(Duration::from_micros(3)),
+            part_time: Some(Duration::from_nanos(1500)),
+        };
+        assert_eq!(
+            record.to_string(),
+            r##"{"day":10,"part":2,"answer":"#.\n\"x\"\\","input":"src/data/day10.txt","parse_ns":3000,"part_ns":1500}"##
+        );
+        let record = Record {
+            answer: Ok(Answer::Num(-42)),
+            ..record
+        };
+        assert!(record.to_string().contains(r#""answer":-42,"#));
+        let record = Record {
+            answer: Err("no \"idea\"".to_string()),
+            parse_time: None,
+            part_time: None,
+            ..record
+        };
+        assert_eq!(
+            record.to_string(),
+            r#"{"day":10,"part":2,"error":"no \"idea\"","input":"src/data/day10.txt","parse_ns":null,"part_ns":null}"#
+        );
+        assert_eq!(string("\u{1}"), r#""\u0001""#);
+    }
+}
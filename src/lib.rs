@@ -0,0 +1,141 @@
+//! Advent of Code 2022. Each `dayN` module holds that day's parsed input model, which implements
+//! [`Solution`](solution::Solution), along with the functions that solve it. The days are all
+//! listed in one table, [`DAYS`], that everything else goes through. The rest is what the
+//! `aoc-2022` runner is built from: reading inputs, checking known answers, timing,
+//! animating, drawing pictures and making up new inputs.
+
+pub mod animate;
+pub mod answers;
+pub mod bench;
+pub mod bitset;
+pub mod cli;
+pub mod cycle;
+pub mod error;
+pub mod examples;
+pub mod generate;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod interval;
+pub mod json;
+pub mod parsers;
+pub mod pool;
+pub mod reference;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod verify;
+pub mod watch;
+
+use animate::Animate;
+use error::Error;
+use generate::Generator;
+use image::Picture;
+use input::InputError;
+use reference::Differ;
+use solution::Parser;
+
+/// Everything there is for one day: how to parse its input, which its solution hangs off, and
+/// whichever extras it has.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    pub parse: Parser,
+    /// A simulation worth watching.
+    pub animate: Option<Animate>,
+    /// A picture of its grid.
+    pub picture: Option<Picture>,
+    /// How to make up an input.
+    pub generate: Option<Generator>,
+    /// Checks the solution against a slow reference solution.
+    pub reference: Option<Differ>,
+}
+
+impl Day {
+    /// A day with a solution and nothing else.
+    pub const fn new(number: u8, parse: Parser) -> Self {
+        Self {
+            number,
+            parse,
+            animate: None,
+            picture: None,
+            generate: None,
+            reference: None,
+        }
+    }
+}
+
+/// Declares each day's module and lists it in [`DAYS`]. A day is `<number> => <module>::<type>`,
+/// the type being its [`Solution`](solution::Solution), then in braces the extras it has:
+/// `animate`, `picture` and `generate` are functions of those names in the module, and `reference`
+/// means the type implements [`Reference`](reference::Reference).
+macro_rules! days {
+    ($($number:literal => $module:ident::$solution:ident $({ $($extra:ident),* })?,)*) => {
+        $(pub mod $module;)*
+
+        /// Every implemented day, in order.
+        pub const DAYS: &[Day] = &[$(
+            Day {
+                $($($extra: Some(extra!($extra, $module, $solution)),)*)?
+                ..Day::new($number, solution::parse::<$module::$solution>)
+            },
+        )*];
+    };
+}
+
+macro_rules! extra {
+    (reference, $module:ident, $solution:ident) => {
+        reference::differences::<$module::$solution>
+    };
+    ($extra:ident, $module:ident, $solution:ident) => {
+        $module::$extra
+    };
+}
+
+days! {
+    1 => day1::Calories { generate },
+    2 => day2::Strategy { generate },
+    3 => day3::Rucksacks { generate },
+    4 => day4::Assignments { generate, reference },
+    5 => day5::CraneGame { generate },
+    6 => day6::Datastream { generate },
+    7 => day7::FileSystem { generate },
+    8 => day8::Forest { picture, generate, reference },
+    9 => day9::Rope { animate, generate },
+    10 => day10::Program { generate },
+    11 => day11::Monkeys { generate, reference },
+    12 => day12::HeightMap { picture, generate, reference },
+    13 => day13::Signal { generate },
+    14 => day14::Cave { animate, picture, generate, reference },
+    15 => day15::Sensors { generate, reference },
+    16 => day16::Valves { generate, reference },
+    17 => day17::Jets { animate, picture, generate, reference },
+}
+
+/// Everything there is for `day`.
+pub fn find(day: u8) -> Result<&'static Day, Error> {
+    DAYS.iter()
+        .find(|d| d.number == day)
+        .ok_or(Error::Unimplemented(day))
+}
+
+pub fn find_day(day: u8) -> Result<Parser, Error> {
+    Ok(find(day)?.parse)
+}
+
+pub fn find_animation(day: u8) -> Result<Animate, Error> {
+    find(day)?.animate.ok_or(Error::NotAnimated(day))
+}
+
+pub fn find_picture(day: u8) -> Result<Picture, Error> {
+    find(day)?.picture.ok_or(Error::NoPicture(day))
+}
+
+pub fn find_generator(day: u8) -> Result<Generator, Error> {
+    find(day)?
+        .generate
+        .ok_or_else(|| InputError::NoGenerator(day).into())
+}
@@ -1,38 +1,375 @@
-#![feature(iter_intersperse)]
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-// pub mod day9;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-
-fn main() {
-    // day1::main();
-    // day2::main();
-    // day3::main();
-    // day4::main();
-    // day5::main();
-    // day6::main();
-    // day7::main();
-    // day8::main();
-    // day9::main();
-    // day10::main();
-    // day11::main();
-    // day12::main();
-    // day13::main();
-    // day14::main();
-    // day15::main();
-    // day16::main();
-    day17::main();
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+};
+
+use aoc_2022::{
+    animate::{Animation, Settings},
+    answers::{self, Answers},
+    bench::{self, Baseline},
+    cli::{self, Command, Days, Format},
+    error::{Error, FileError},
+    find_animation, find_day, find_generator, find_picture, generate, image,
+    input::{self, Source},
+    pool,
+    runner::{self, Outcome},
+    scaffold,
+    solution::Answer,
+    verify,
+    watch::{self, Watcher},
+    Day, DAYS,
+};
+
+/// Gives whether every part could be answered.
+fn run_day(day: u8, part: Option<u8>, source: &Source, format: Format) -> Result<bool, Error> {
+    let run = runner::run_day(day, find_day(day)?, source, part);
+    match (format, &run.outcome) {
+        (Format::Json, _) => {
+            for record in run.records(part) {
+                println!("{}", record);
+            }
+        }
+        (Format::Text, Outcome::Solved { parts, .. }) => {
+            for part in parts {
+                match &part.answer {
+                    // multi-line answers (pictures) read better starting on their own line
+                    Ok(Answer::Text(text)) if text.contains('\n') => {
+                        println!("day {} part {}:\n{}", day, part.part, text)
+                    }
+                    Ok(answer) => println!("day {} part {}: {}", day, part.part, answer),
+                    // reported with the errors
+                    Err(_) => (),
+                }
+            }
+        }
+        (Format::Text, _) => (),
+    }
+    let errors = run.errors();
+    for e in &errors {
+        eprintln!("error: {}", e);
+    }
+    Ok(errors.is_empty())
+}
+
+/// Runs the day on each of its inputs whenever one of them changes, until interrupted.
+fn watch_day(day: u8, part: Option<u8>, source: Source) -> Result<(), Error> {
+    let parse = find_day(day)?;
+    let mut watcher = Watcher::new(day, source);
+    let mut previous = Vec::new();
+    loop {
+        let changed = watcher.poll();
+        if !changed.is_empty() {
+            let names: Vec<String> = changed
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            println!("changed: {}", names.join(", "));
+            let runs: Vec<_> = watcher
+                .files()
+                .map(|path| runner::run_day(day, parse, &Source::File(path.to_owned()), part))
+                .collect();
+            println!("{}", watch::report(&runs, &previous));
+            previous = runs;
+        }
+        thread::sleep(watch::POLL_INTERVAL);
+    }
+}
+
+/// Runs every day at once. Gives whether they all got their answers.
+fn run_all(part: Option<u8>, source: &Source, format: Format, jobs: Option<usize>) -> bool {
+    let threads = jobs.unwrap_or_else(pool::default_threads);
+    let summary = runner::run_all(DAYS, source, part, threads);
+    match format {
+        Format::Text => println!("{}", summary),
+        Format::Json => {
+            for run in &summary.runs {
+                for record in run.records(part) {
+                    println!("{}", record);
+                }
+                for e in run.errors() {
+                    eprintln!("error: {}", e);
+                }
+            }
+        }
+    }
+    !summary.failed()
+}
+
+/// Times one day, printing a row per stage and adding the timings to `results`.
+fn bench_day(
+    day: u8,
+    source: &Source,
+    iterations: usize,
+    compare: Option<&Baseline>,
+    results: &mut Baseline,
+) -> Result<(), Error> {
+    let parse = find_day(day)?;
+    let input = source.load(day)?;
+    let stats = bench::bench(parse, &input, iterations)?;
+    for (stage, stats) in bench::STAGES.iter().zip(&stats) {
+        let before = compare.and_then(|baseline| baseline.get(day, stage));
+        println!("{}", bench::row(day, stage, stats, before));
+        results.insert(day, stage, *stats);
+    }
+    Ok(())
+}
+
+/// Gives whether every day could be timed.
+fn bench_days(
+    days: Days,
+    source: &Source,
+    iterations: usize,
+    save: Option<PathBuf>,
+    compare: Option<PathBuf>,
+) -> Result<bool, Error> {
+    let compare = compare.map(|path| Baseline::load(&path)).transpose()?;
+    let mut results = Baseline::default();
+    println!("{}", bench::HEADER);
+    let ok = match days {
+        Days::One(day) => {
+            bench_day(day, source, iterations, compare.as_ref(), &mut results)?;
+            true
+        }
+        Days::All => {
+            let mut ok = true;
+            for &Day { number: day, .. } in DAYS {
+                let timed = bench_day(day, source, iterations, compare.as_ref(), &mut results);
+                if let Err(e) = timed {
+                    eprintln!("error: {}", e);
+                    ok = false;
+                }
+            }
+            ok
+        }
+    };
+    if let Some(path) = save {
+        // keep whatever else was saved there before, only replacing the days just timed
+        let mut baseline = Baseline::load(&path)?;
+        baseline.extend(results);
+        baseline.save(&path)?;
+    }
+    Ok(ok)
+}
+
+/// Plays a day's simulation on the terminal, or with `record`, saves every frame there instead.
+fn animate_day(
+    day: u8,
+    source: &Source,
+    settings: Settings,
+    record: Option<PathBuf>,
+) -> Result<(), Error> {
+    let animate = find_animation(day)?;
+    let input = source.load(day)?;
+    let mut simulation = animate(&input)?;
+    match record {
+        None => {
+            Animation::terminal(settings).run(simulation.as_mut());
+        }
+        Some(path) => {
+            if !settings.stops(simulation.as_ref()) {
+                return Err(Error::Endless {
+                    day,
+                    output: "--record",
+                });
+            }
+            let mut animation = Animation::headless(settings);
+            let steps = animation.run(simulation.as_mut());
+            let frames = animation.frames();
+            fs::write(&path, frames.join("\n\n") + "\n").map_err(|err| FileError::Io {
+                path: path.clone(),
+                err,
+            })?;
+            println!(
+                "recorded {} frames of {} steps in {}",
+                frames.len(),
+                steps,
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn draw_image(
+    day: u8,
+    source: &Source,
+    out: Option<PathBuf>,
+    scale: usize,
+    sequence: Option<PathBuf>,
+    settings: Settings,
+) -> Result<(), Error> {
+    match sequence {
+        None => {
+            let picture = find_picture(day)?;
+            let image = picture(&source.load(day)?)?;
+            let path = out.unwrap_or_else(|| format!("day{}.{}", day, image.extension()).into());
+            image.save(&path, scale)?;
+            println!("saved {}", path.display());
+        }
+        Some(dir) => {
+            let animate = find_animation(day)?;
+            let mut simulation = animate(&source.load(day)?)?;
+            if !settings.stops(simulation.as_ref()) {
+                return Err(Error::Endless {
+                    day,
+                    output: "--sequence",
+                });
+            }
+            let frames = image::sequence(simulation.as_mut(), &settings, &dir, scale)?;
+            println!("saved {} frames in {}", frames, dir.display());
+        }
+    }
+    Ok(())
+}
+
+fn print_generated(day: u8, size: usize, seed: u64) -> Result<(), Error> {
+    print!("{}", generate::generate(find_generator(day)?, size, seed));
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<(), Error> {
+    for path in scaffold::new_day(Path::new("."), day)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Gives whether everything that could be checked was right.
+fn verify_days(
+    inputs: Option<PathBuf>,
+    answers: Option<PathBuf>,
+    record: bool,
+) -> Result<bool, Error> {
+    let dir = inputs.unwrap_or_else(input::default_dir);
+    let path = answers.unwrap_or_else(|| dir.join(answers::ANSWERS_FILE));
+    let mut answers = Answers::load(&path)?;
+    let report = verify::verify(DAYS, &dir, &answers);
+    println!("{}", report);
+    if record {
+        let added = report.record(&mut answers);
+        if added > 0 {
+            answers.save(&path)?;
+        }
+        println!("recorded {} new answers in {}", added, path.display());
+    }
+    Ok(!report.failed())
+}
+
+fn main() -> ExitCode {
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Verify {
+            inputs,
+            answers,
+            record,
+        } => match verify_days(inputs, answers, record) {
+            Ok(true) => (),
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Bench {
+            days,
+            input,
+            iterations,
+            save,
+            compare,
+        } => match bench_days(days, &input.unwrap_or_default(), iterations, save, compare) {
+            Ok(true) => (),
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Animate {
+            day,
+            input,
+            settings,
+            record,
+        } => {
+            if let Err(e) = animate_day(day, &input.unwrap_or_default(), settings, record) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Image {
+            day,
+            input,
+            out,
+            scale,
+            sequence,
+            settings,
+        } => {
+            let source = input.unwrap_or_default();
+            if let Err(e) = draw_image(day, &source, out, scale, sequence, settings) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Generate { day, size, seed } => {
+            if let Err(e) = print_generated(day, size, seed) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::New { day } => {
+            if let Err(e) = new_day(day) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Run {
+            days: Days::One(day),
+            part,
+            input,
+            watch: true,
+            ..
+        } => {
+            if let Err(e) = watch_day(day, part, input.unwrap_or_default()) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Run {
+            days: Days::One(day),
+            part,
+            input,
+            format,
+            // only ever given with --all
+            jobs: _,
+            watch: false,
+        } => match run_day(day, part, &input.unwrap_or_default(), format) {
+            Ok(true) => (),
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Run {
+            days: Days::All,
+            part,
+            input,
+            format,
+            jobs,
+            watch: _,
+        } => {
+            if !run_all(part, &input.unwrap_or_default(), format, jobs) {
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
 }
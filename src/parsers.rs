@@ -0,0 +1,149 @@
+//! nom parsers more than one day needs, plus wrappers that turn a parser's result into a
+//! located [`ParseError`] and insist that it used up the whole input.
+
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    character::complete::{char, digit1, space0},
+    combinator::{map, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::pair,
+    IResult,
+};
+
+use crate::{error::ParseError, geometry::Point};
+
+/// Parses what `digits` recognised into a `T`. Digits that don't fit are a failure rather than
+/// an error, so an `alt` further out doesn't go on to try something else.
+fn number<'a, T: FromStr>(
+    mut digits: impl FnMut(&'a str) -> IResult<&'a str, &'a str>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    move |input| {
+        let (rest, found) = digits(input)?;
+        match found.parse() {
+            Ok(val) => Ok((rest, val)),
+            Err(_) => Err(nom::Err::Failure(Error::new(input, ErrorKind::MapRes))),
+        }
+    }
+}
+
+/// A number without a sign, like `42`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    number(digit1)(input)
+}
+
+/// A number with an optional `-` or `+` in front, like `-42`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    number(recognize(pair(opt(alt((char('-'), char('+')))), digit1)))(input)
+}
+
+/// At least one `item`, separated by commas with or without spaces after them.
+pub fn comma_list<'a, O>(
+    item: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(pair(char(','), space0), item)
+}
+
+/// Two of `item` with a comma between, like `3,4`.
+pub fn pair_of<'a, O>(
+    mut item: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (O, O)> {
+    move |input| {
+        let (input, x) = item(input)?;
+        let (input, _) = char(',')(input)?;
+        let (input, y) = item(input)?;
+        Ok((input, (x, y)))
+    }
+}
+
+/// An `x,y` pair of coordinates.
+pub fn point<'a, T>(
+    coordinate: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Point<T>> {
+    map(pair_of(coordinate), Point::from)
+}
+
+/// Runs `parser` over the whole of a day's input. Anything but whitespace left over is an error.
+pub fn all<'a, O>(
+    day: u8,
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, ParseError> {
+    let (rest, parsed) = parser(input).map_err(|e| ParseError::from_nom(day, input, e))?;
+    ParseError::check_rest(day, input, rest)?;
+    Ok(parsed)
+}
+
+/// Runs `parser` over every line of a day's input, each of which it has to use up.
+pub fn each_line<'a, O>(
+    day: u8,
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<Vec<O>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (rest, parsed) = parser(line).map_err(|e| ParseError::from_nom(day, input, e))?;
+            ParseError::check_rest(day, input, rest)?;
+            Ok(parsed)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::forall;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(unsigned::<u8>("42x"), Ok(("x", 42)));
+        assert!(unsigned::<u8>("-42").is_err());
+        assert_eq!(signed::<i32>("-42"), Ok(("", -42)));
+        assert_eq!(signed::<i32>("+7,"), Ok((",", 7)));
+        assert_eq!(
+            unsigned::<u8>("256"),
+            Err(nom::Err::Failure(Error::new("256", ErrorKind::MapRes)))
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            comma_list(unsigned::<u32>)("1, 2,3"),
+            Ok(("", vec![1, 2, 3]))
+        );
+        assert_eq!(
+            point(signed::<i32>)("-1,5 ->"),
+            Ok((" ->", Point::new(-1, 5)))
+        );
+    }
+
+    #[test]
+    fn test_whole_input() {
+        assert_eq!(all(1, "12\n", unsigned::<u32>), Ok(12));
+        let err = all(1, "12 13", unsigned::<u32>).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+        let err = each_line(1, "1\n2\nx", unsigned::<u32>).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(each_line(1, "1\n2", unsigned::<u32>), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_number_round_trip() {
+        forall(500, |rng| {
+            let n = rng.next_u64();
+            assert_eq!(unsigned::<u64>(&n.to_string()), Ok(("", n)));
+            let n = rng.range(i64::MIN..=i64::MAX);
+            assert_eq!(signed::<i64>(&n.to_string()), Ok(("", n)));
+            // one digit more than fits is too large, not some other number
+            let too_large = format!("{}{}", u64::MAX, rng.below(10));
+            assert!(matches!(
+                unsigned::<u64>(&too_large),
+                Err(nom::Err::Failure(_))
+            ));
+        });
+    }
+}
@@ -0,0 +1,96 @@
+//! A fixed number of worker threads sharing out a list of jobs, where a job that panics only
+//! takes itself down.
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+/// How many workers to use when not told otherwise: one per core, or just the one if that can't
+/// be found out.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// What a panic said, if it said it with a string (which `panic!` and friends do).
+fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "panicked without a message".to_string(),
+        },
+    }
+}
+
+/// Runs `f`, turning a panic into an error holding its message. The panic is still reported on
+/// stderr as usual.
+pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(message)
+}
+
+/// Does `work` on every job using up to `threads` threads, and gives the results in the same order
+/// as the jobs, with an `Err` holding the message for each job that panicked.
+pub fn run<T, R>(
+    jobs: Vec<T>,
+    threads: usize,
+    work: impl Fn(T) -> R + Sync,
+) -> Vec<Result<R, String>>
+where
+    T: Send,
+    R: Send,
+{
+    let count = jobs.len();
+    let queue = Mutex::new(jobs.into_iter().enumerate());
+    let (send, results) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, count.max(1)) {
+            let send = send.clone();
+            let (queue, work) = (&queue, &work);
+            scope.spawn(move || loop {
+                // the lock is only held to take the next job, never while one runs, so a panic
+                // can't poison it
+                let next = queue.lock().unwrap().next();
+                let Some((i, job)) = next else { break };
+                if send.send((i, catch(|| work(job)))).is_err() {
+                    break;
+                }
+            });
+        }
+    });
+    drop(send);
+    let mut ordered: Vec<_> = results.into_iter().collect();
+    ordered.sort_by_key(|&(i, _)| i);
+    ordered.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_in_order() {
+        let jobs: Vec<u64> = (0..50).collect();
+        let results = run(jobs, 4, |n| n * n);
+        let expected: Vec<_> = (0..50).map(|n| Ok(n * n)).collect();
+        assert_eq!(results, expected);
+        assert_eq!(run(Vec::<u8>::new(), 4, |n| n), vec![]);
+    }
+
+    #[test]
+    fn test_panics_stay_with_their_job() {
+        let results = run(vec![1, 0, 3], 2, |n| {
+            if n == 0 {
+                panic!("no zeroes");
+            }
+            10 / n
+        });
+        assert_eq!(results, vec![Ok(10), Err("no zeroes".to_string()), Ok(3)]);
+        assert_eq!(
+            catch(|| panic!("{} apples", 3)),
+            Err::<(), _>("3 apples".into())
+        );
+    }
+}
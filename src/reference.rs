@@ -0,0 +1,93 @@
+//! Slow but obviously correct ways of answering some of the days, kept alongside the fast
+//! solutions to check them against. Nothing runs these for real; the test at the bottom runs both
+//! on generated inputs (small enough for the slow way) and expects the same answers.
+
+use std::fmt::Display;
+
+use crate::{
+    error::Error,
+    solution::{Answer, Solution},
+};
+
+/// A day's answers worked out the long way round, from the same parsed input as its
+/// [`Solution`].
+pub trait Reference: Solution {
+    /// `None` where there's no slower way worth having, because the solution already is one.
+    fn reference_part1(&self) -> Option<Answer>;
+    /// `None` where there's no slower way worth having, or no slower way that ever finishes.
+    fn reference_part2(&self) -> Option<Answer>;
+}
+
+/// A part where the solution and its reference disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub part: u8,
+    pub answer: Answer,
+    pub reference: Answer,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "part {} answered {} but the reference says {}",
+            self.part, self.answer, self.reference
+        )
+    }
+}
+
+/// Compares a day's solution with its reference on an input, see [`differences`].
+pub type Differ = fn(&str) -> Result<Vec<Difference>, Error>;
+
+/// Parses `input` as `R` and answers each part both ways, giving the parts that differ. A part
+/// with no answer is an error, as the reference is only asked about inputs that have one.
+pub fn differences<R: Reference>(input: &str) -> Result<Vec<Difference>, Error> {
+    let solution = R::parse(input)?;
+    let references = [solution.reference_part1(), solution.reference_part2()];
+    let mut differences = Vec::new();
+    for (part, reference) in (1..=2).zip(references) {
+        let Some(reference) = reference else {
+            continue;
+        };
+        let answer = solution.part(part)?;
+        if answer != reference {
+            differences.push(Difference {
+                part,
+                answer,
+                reference,
+            });
+        }
+    }
+    Ok(differences)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{find_generator, generate::generate, DAYS};
+
+    #[test]
+    fn test_solutions_match_references() {
+        for day in DAYS {
+            let Some(differ) = day.reference else {
+                continue;
+            };
+            let day = day.number;
+            let generator = find_generator(day).unwrap();
+            for seed in 0..4 {
+                for size in [4, 12, 24] {
+                    let input = generate(generator, size, seed);
+                    let differences = differ(&input).unwrap();
+                    assert!(
+                        differences.is_empty(),
+                        "day {} size {} seed {}: {}\n{}",
+                        day,
+                        size,
+                        seed,
+                        differences[0],
+                        input
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! A small seeded random number generator, enough for property tests and for making up puzzle
+//! inputs without pulling in a crate for it. The same seed always gives the same numbers.
+
+use std::{fmt::Debug, ops::RangeInclusive};
+
+/// SplitMix64: tiny, fast and good enough for anything but cryptography.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Somewhere in `0..n`. The slight bias towards small numbers doesn't matter here.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "nothing is below 0");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Somewhere in `range`, ends included.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "{}..={} is empty", start, end);
+        let width = end.abs_diff(start).wrapping_add(1);
+        match width {
+            // the whole of i64
+            0 => self.next_u64() as i64,
+            width => start.wrapping_add((self.next_u64() % width) as i64),
+        }
+    }
+
+    /// `true` about one time in `n`.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// `len` characters, each one of `alphabet`.
+    pub fn string(&mut self, alphabet: &str, len: usize) -> String {
+        let chars: Vec<char> = alphabet.chars().collect();
+        (0..len).map(|_| *self.pick(&chars)).collect()
+    }
+
+    /// Fisher-Yates.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Checks `property` against `cases` differently seeded generators, saying which seed it failed
+/// for so the failure can be reproduced.
+#[cfg(test)]
+pub(crate) fn forall(cases: u64, mut property: impl FnMut(&mut Rng)) {
+    for seed in 0..cases {
+        if let Err(message) = crate::pool::catch(|| property(&mut Rng::new(seed))) {
+            panic!("failed with seed {}: {}", seed, message);
+        }
+    }
+}
+
+/// `text` with a few characters dropped, added or swapped for others, mostly ones that could be
+/// in a puzzle input but now and then something further afield.
+#[cfg(test)]
+pub(crate) fn mutate(rng: &mut Rng, text: &str) -> String {
+    const NEAR: &str = "0123456789abcxyzABC -=+,:;[]()#.\n";
+    const FAR: &str = "\0\t\r\u{7f}é→🦀";
+    let mut chars: Vec<char> = text.chars().collect();
+    for _ in 0..=rng.below(3) {
+        let at = rng.below(chars.len() + 1);
+        let new = match rng.one_in(8) {
+            true => rng.string(FAR, 1),
+            false => rng.string(NEAR, 1),
+        };
+        let new = new.chars().next().unwrap();
+        match rng.below(3) {
+            0 if at < chars.len() => {
+                chars.remove(at);
+            }
+            1 if at < chars.len() => chars[at] = new,
+            _ => chars.insert(at, new),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// What a nom parser made of the whole of its input, or `None` if it failed or stopped short.
+#[cfg(test)]
+pub(crate) fn whole<T, E>(parsed: Result<(&str, T), E>) -> Option<T> {
+    match parsed {
+        Ok(("", value)) => Some(value),
+        _ => None,
+    }
+}
+
+/// The checks every parser gets, on `cases` values from `make` along with the text each should be
+/// parsed from. That text has to parse back to the value. With a few characters changed it has
+/// to either not parse at all, or give something that `render`s to text parsing back to the same
+/// thing, so nothing is accepted that can't be written down again. And strings of `garbage`
+/// characters mustn't parse. `parse` gives `None` for text it can't take all of, see [`whole`].
+#[cfg(test)]
+pub(crate) fn check_parser<T: PartialEq + Debug>(
+    cases: u64,
+    garbage: &str,
+    mut make: impl FnMut(&mut Rng) -> (T, String),
+    render: impl Fn(&T) -> String,
+    parse: impl Fn(&str) -> Option<T>,
+) {
+    forall(cases, |rng| {
+        let (value, text) = make(rng);
+        assert_eq!(parse(&text), Some(value), "parsing {:?}", text);
+        let mutated = mutate(rng, &text);
+        if let Some(value) = parse(&mutated) {
+            let rendered = render(&value);
+            assert_eq!(
+                parse(&rendered),
+                Some(value),
+                "{:?} parsed, but not once written as {:?}",
+                mutated,
+                rendered
+            );
+        }
+        let len = rng.below(40);
+        let garbage = rng.string(garbage, len);
+        assert_eq!(parse(&garbage), None, "parsing {:?}", garbage);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_repeatable() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        let first: Vec<_> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(Rng::new(8).next_u64(), first[0]);
+    }
+
+    #[test]
+    fn test_ranges() {
+        forall(100, |rng| {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert_eq!(rng.range(5..=5), 5);
+            assert!(rng.below(10) < 10);
+            let _ = rng.range(i64::MIN..=i64::MAX);
+            assert!(rng.string("ab", 4).chars().all(|c| c == 'a' || c == 'b'));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "parsed, but not once written as")]
+    fn test_check_parser_catches_what_cant_be_written() {
+        // keeps any spaces, which `render` leaves out
+        let parse = |text: &str| {
+            let word = text.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ');
+            (word && !text.is_empty()).then(|| text.to_string())
+        };
+        check_parser(
+            500,
+            "#",
+            |rng| {
+                let len = 1 + rng.below(5);
+                let word = rng.string("abc", len);
+                (word.clone(), word)
+            },
+            |word| word.replace(' ', ""),
+            parse,
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! Running days and collecting what they came to. `run --all` makes each day a job on a
+//! [`pool`], so the whole year runs at once and one day going wrong (even panicking) doesn't stop
+//! the rest, then prints a [`Summary`] of them all.
+
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::Error,
+    input::Source,
+    json::Record,
+    pool,
+    solution::{Answer, Parser},
+    Day,
+};
+
+/// Why a part didn't come to an answer.
+pub enum Failure {
+    /// The part had no answer for the input, see [`Error::NoAnswer`].
+    Error(Error),
+    /// The panic message.
+    Panicked(String),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error(e) => e.fmt(f),
+            Self::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+pub struct PartRun {
+    pub part: u8,
+    pub answer: Result<Answer, Failure>,
+    pub time: Duration,
+}
+
+pub enum Outcome {
+    Solved {
+        parse_time: Duration,
+        parts: Vec<PartRun>,
+    },
+    /// The input couldn't be read or parsed.
+    Failed(Error),
+    /// Parsing panicked.
+    Panicked(String),
+}
+
+pub struct DayRun {
+    pub day: u8,
+    /// Where the input came from, see [`Source::describe`].
+    pub input: String,
+    pub outcome: Outcome,
+}
+
+/// The parts to run, both unless one is asked for.
+fn parts(part: Option<u8>) -> Vec<u8> {
+    part.map_or(vec![1, 2], |part| vec![part])
+}
+
+/// Parses the day's input and runs the parts, timing each.
+pub fn run_day(day: u8, parse: Parser, source: &Source, part: Option<u8>) -> DayRun {
+    let run = |outcome| DayRun {
+        day,
+        input: source.describe(day),
+        outcome,
+    };
+    let input = match source.load(day) {
+        Ok(input) => input,
+        Err(e) => return run(Outcome::Failed(e)),
+    };
+    let start = Instant::now();
+    let solution = match pool::catch(|| parse(&input)) {
+        Ok(Ok(solution)) => solution,
+        Ok(Err(e)) => return run(Outcome::Failed(e.into())),
+        Err(message) => return run(Outcome::Panicked(message)),
+    };
+    let parse_time = start.elapsed();
+    let parts = parts(part)
+        .into_iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = match pool::catch(|| solution.part(part)) {
+                Ok(Ok(answer)) => Ok(answer),
+                Ok(Err(e)) => Err(Failure::Error(e)),
+                Err(message) => Err(Failure::Panicked(message)),
+            };
+            PartRun {
+                part,
+                answer,
+                time: start.elapsed(),
+            }
+        })
+        .collect();
+    run(Outcome::Solved { parse_time, parts })
+}
+
+pub struct Summary {
+    pub runs: Vec<DayRun>,
+    pub threads: usize,
+    /// How long it all took from start to finish, rather than added up.
+    pub wall_time: Duration,
+}
+
+/// Runs every one of `days` on up to `threads` threads.
+pub fn run_all(days: &[Day], source: &Source, part: Option<u8>, threads: usize) -> Summary {
+    let start = Instant::now();
+    let results = pool::run(days.to_vec(), threads, |day| {
+        run_day(day.number, day.parse, source, part)
+    });
+    let runs = days
+        .iter()
+        .zip(results)
+        .map(|(&Day { number: day, .. }, result)| {
+            // run_day catches its own panics, so this is only for the ones it can't
+            result.unwrap_or_else(|message| DayRun {
+                day,
+                input: source.describe(day),
+                outcome: Outcome::Panicked(message),
+            })
+        })
+        .collect();
+    Summary {
+        runs,
+        threads,
+        wall_time: start.elapsed(),
+    }
+}
+
+impl DayRun {
+    /// A record of each of the parts that were asked for (all of them unless `part` says), for
+    /// `--format json`. Parts without an answer have the error instead, which for a day that
+    /// couldn't be parsed is the same for every part.
+    pub fn records(&self, part: Option<u8>) -> Vec<Record> {
+        let record = |part, answer, parse_time, part_time| Record {
+            day: self.day,
+            part,
+            answer,
+            input: self.input.clone(),
+            parse_time,
+            part_time,
+        };
+        let failed = match &self.outcome {
+            Outcome::Solved { parse_time, parts } => {
+                return parts
+                    .iter()
+                    .map(|part| {
+                        let answer = part.answer.as_ref().map(Answer::clone);
+                        let answer = answer.map_err(Failure::to_string);
+                        record(part.part, answer, Some(*parse_time), Some(part.time))
+                    })
+                    .collect()
+            }
+            Outcome::Failed(e) => e.to_string(),
+            Outcome::Panicked(message) => format!("panicked: {}", message),
+        };
+        parts(part)
+            .into_iter()
+            .map(|part| record(part, Err(failed.clone()), None, None))
+            .collect()
+    }
+
+    /// What went wrong, if anything did.
+    pub fn errors(&self) -> Vec<String> {
+        match &self.outcome {
+            Outcome::Solved { parts, .. } => parts
+                .iter()
+                .filter_map(|part| match part.answer.as_ref().err()? {
+                    Failure::Error(e) => {
+                        Some(format!("day {} part {}: {}", self.day, part.part, e))
+                    }
+                    Failure::Panicked(message) => Some(format!(
+                        "day {} part {} panicked: {}",
+                        self.day, part.part, message
+                    )),
+                })
+                .collect(),
+            Outcome::Failed(e) => vec![e.to_string()],
+            Outcome::Panicked(message) => vec![format!("day {} panicked: {}", self.day, message)],
+        }
+    }
+}
+
+impl Summary {
+    pub fn failed(&self) -> bool {
+        self.runs.iter().any(|run| !run.errors().is_empty())
+    }
+}
+
+/// `1 thing` or `2 things`.
+fn count(n: usize, thing: &str) -> String {
+    match n {
+        1 => format!("1 {}", thing),
+        n => format!("{} {}s", n, thing),
+    }
+}
+
+/// How an answer sits in one table cell. Pictures don't fit, so they go underneath.
+fn cell(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(text) if text.contains('\n') => "(see below)".to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let answer_width = self
+            .runs
+            .iter()
+            .flat_map(|run| match &run.outcome {
+                Outcome::Solved { parts, .. } => parts.as_slice(),
+                _ => &[],
+            })
+            .filter_map(|part| part.answer.as_ref().ok().map(|answer| cell(answer).len()))
+            .chain(["answer".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "day part {:aw$}       parse        part",
+            "answer",
+            aw = answer_width
+        )?;
+        let (mut answers, mut errors) = (0, 0);
+        let mut pictures = Vec::new();
+        for run in &self.runs {
+            let Outcome::Solved { parse_time, parts } = &run.outcome else {
+                // the first line of a parse error is the summary, the rest points at the input
+                let error = run.errors().remove(0);
+                let summary = error.lines().next().unwrap_or_default();
+                writeln!(f, "{:>3}    - ERROR {}", run.day, summary)?;
+                errors += 1;
+                continue;
+            };
+            for part in parts {
+                let answer = match &part.answer {
+                    Ok(answer) => answer,
+                    Err(Failure::Error(e)) => {
+                        writeln!(f, "{:>3} {:>4} ERROR {}", run.day, part.part, e)?;
+                        errors += 1;
+                        continue;
+                    }
+                    Err(Failure::Panicked(message)) => {
+                        writeln!(f, "{:>3} {:>4} PANIC {}", run.day, part.part, message)?;
+                        errors += 1;
+                        continue;
+                    }
+                };
+                answers += 1;
+                if let Answer::Text(text) = answer {
+                    if text.contains('\n') {
+                        pictures.push((run.day, part.part, text));
+                    }
+                }
+                writeln!(
+                    f,
+                    "{:>3} {:>4} {:aw$} {:>11.2?} {:>11.2?}",
+                    run.day,
+                    part.part,
+                    cell(answer),
+                    parse_time,
+                    part.time,
+                    aw = answer_width
+                )?;
+            }
+        }
+        for (day, part, picture) in pictures {
+            writeln!(f, "\nday {} part {}:\n{}", day, part, picture)?;
+        }
+        write!(
+            f,
+            "{}, {} in {:.2?} on {}",
+            count(answers, "answer"),
+            count(errors, "error"),
+            self.wall_time,
+            count(self.threads, "thread")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{error::ParseError, solution::Solution};
+
+    /// Answers part 1 and panics on part 2, or fails to parse if there's no input. Has no answer
+    /// for either part if the input is `?`.
+    struct Flaky(bool);
+
+    impl Solution for Flaky {
+        fn parse(input: &str) -> Result<Self, ParseError> {
+            match input {
+                "" => Err(ParseError::new(1, input, input, "expected something")),
+                _ => Ok(Flaky(input == "?")),
+            }
+        }
+        fn part1(&self) -> Result<Answer, Error> {
+            match self.0 {
+                true => Err(Error::NoAnswer("no idea")),
+                false => Ok("fine".into()),
+            }
+        }
+        fn part2(&self) -> Result<Answer, Error> {
+            match self.0 {
+                true => Err(Error::NoAnswer("no idea")),
+                false => panic!("not today"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_all() {
+        let dir = std::env::temp_dir().join("aoc-2022-runner-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day1.txt"), "").unwrap();
+        std::fs::write(dir.join("day2.txt"), "x").unwrap();
+        std::fs::write(dir.join("day3.txt"), "?").unwrap();
+        let days = &[
+            Day::new(1, crate::solution::parse::<Flaky>),
+            Day::new(2, crate::solution::parse::<Flaky>),
+            Day::new(3, crate::solution::parse::<Flaky>),
+        ];
+        let summary = run_all(days, &Source::Dir(dir), None, 2);
+        assert!(summary.failed());
+        assert!(matches!(summary.runs[0].outcome, Outcome::Failed(_)));
+        assert_eq!(
+            summary.runs[1].errors(),
+            vec!["day 2 part 2 panicked: not today".to_string()]
+        );
+        let records = summary.runs[1].records(None);
+        assert_eq!(records[0].answer, Ok(Answer::from("fine")));
+        assert_eq!(records[1].answer, Err("panicked: not today".to_string()));
+        assert_eq!(summary.runs[2].errors()[0], "day 3 part 1: no idea");
+        let records = summary.runs[2].records(None);
+        assert_eq!(records[0].answer, Err("no idea".to_string()));
+        let records = summary.runs[0].records(Some(2));
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].part, records[0].parse_time), (2, None));
+        assert!(records[0].answer.is_err());
+        let table = summary.to_string();
+        assert!(table.contains("  2    1 fine  "));
+        assert!(table.contains("  2    2 PANIC not today"));
+        assert!(table.contains("  3    2 ERROR no idea"));
+        assert!(table.ends_with("on 2 threads"));
+        assert!(table.contains("1 answer, 4 errors"));
+    }
+}
@@ -0,0 +1,254 @@
+//! `new <day>`: starting a day off with a module that already fits the runner, an empty example
+//! input to paste the puzzle's example into, and the lines in `src/lib.rs` and
+//! `src/examples.rs` that register them.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, FileError};
+
+/// What a new day starts as, with `{day}` standing for its number.
+const TEMPLATE: &str = r#"use nom::{character::complete::alphanumeric0, IResult};
+
+use crate::{
+    error::{Error, ParseError},
+    parsers,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = {day};
+
+/// Letters and digits, until it's known what a line really holds.
+fn parse_line(input: &str) -> IResult<&str, String> {
+    let (input, line) = alphanumeric0(input)?;
+    Ok((input, line.to_string()))
+}
+
+/// Each line of the input, until there's something better to parse it into.
+pub struct Puzzle {
+    pub lines: Vec<String>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            lines: parsers::each_line(DAY, input, parse_line)?,
+        })
+    }
+
+    fn part1(&self) -> Result<Answer, Error> {
+        Err(Error::NoAnswer("not solved yet"))
+    }
+
+    fn part2(&self) -> Result<Answer, Error> {
+        Err(Error::NoAnswer("not solved yet"))
+    }
+}
+"#;
+
+/// The day's entry in [`EXAMPLES`](crate::examples::EXAMPLES). Its answers are placeholders, so
+/// the examples test fails until the day is solved and they're filled in.
+const EXAMPLE_TEMPLATE: &str = r#"    Example {
+        day: {day},
+        variant: None,
+        input: include_str!("examples/day{day}.txt"),
+        // the answers the puzzle gives for its example
+        part1: Some("part 1's answer"),
+        part2: Some("part 2's answer"),
+        parse: None,
+    },"#;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// Not overwritten, in case it holds a solution.
+    Exists(PathBuf),
+    Registered(u8),
+    /// The file doesn't look the way it's expected to, so it's not clear where the day goes.
+    NoRegistry(PathBuf),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::Registered(day) => write!(f, "day {} is already registered", day),
+            Self::NoRegistry(path) => {
+                write!(
+                    f,
+                    "couldn't find where to add the day in {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+/// The source of a new day's module.
+pub fn skeleton(day: u8) -> String {
+    TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/// The day of the list entry starting on line `i`: either `3 => ...,`, `example!(3, ...),` or an
+/// `Example {` with its `day: 3,` on the next line.
+fn entry_day(lines: &[&str], i: usize) -> Option<u8> {
+    let line = lines[i].strip_prefix("    ")?;
+    if line == "Example {" {
+        let day = lines.get(i + 1)?.trim().strip_prefix("day: ")?;
+        return day.strip_suffix(',')?.parse().ok();
+    }
+    if let Some((day, _)) = line.split_once(" => ") {
+        return day.parse().ok();
+    }
+    let line = line.strip_prefix("example!").unwrap_or(line);
+    let (day, _) = line.strip_prefix('(')?.split_once(',')?;
+    day.parse().ok()
+}
+
+/// `text` with `entry` added to the list from the line starting `list` to the line `end`, before
+/// the first entry for a later day. `None` if there's no such list.
+fn insert_entry(text: &str, list: &str, end: &str, entry: &str, day: u8) -> Option<String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().position(|line| line.starts_with(list))?;
+    let end = start + lines[start..].iter().position(|&line| line == end)?;
+    let at = (start + 1..end)
+        .find(|&i| entry_day(&lines, i).is_some_and(|d| d > day))
+        .unwrap_or(end);
+    lines.insert(at, entry);
+    Some(lines.join("\n") + "\n")
+}
+
+/// `lib` (the text of `src/lib.rs`) with the day added to the `days!` table, which declares its
+/// module and lists it in [`DAYS`](crate::DAYS).
+fn register(lib: &str, day: u8) -> Option<String> {
+    let entry = format!("    {} => day{}::Puzzle,", day, day);
+    insert_entry(lib, "days! {", "}", &entry, day)
+}
+
+/// `examples` (the text of `src/examples.rs`) with an example for the day added.
+fn register_example(examples: &str, day: u8) -> Option<String> {
+    let entry = EXAMPLE_TEMPLATE.replace("{day}", &day.to_string());
+    insert_entry(examples, "pub const EXAMPLES:", "];", &entry, day)
+}
+
+/// Writes a new day into the crate at `root`, giving the files it wrote or changed.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, Error> {
+    let src = root.join("src");
+    let module = src.join(format!("day{}.rs", day));
+    let example = src.join("examples").join(format!("day{}.txt", day));
+    let lib = src.join("lib.rs");
+    let examples = src.join("examples.rs");
+    if module.exists() {
+        return Err(ScaffoldError::Exists(module).into());
+    }
+    let io = |path: &Path| {
+        let path = path.to_owned();
+        move |err| FileError::Io { path, err }
+    };
+    let lib_text = fs::read_to_string(&lib).map_err(io(&lib))?;
+    let lines: Vec<&str> = lib_text.lines().collect();
+    if (0..lines.len()).any(|i| entry_day(&lines, i) == Some(day)) {
+        return Err(ScaffoldError::Registered(day).into());
+    }
+    let registered = register(&lib_text, day).ok_or(ScaffoldError::NoRegistry(lib.clone()))?;
+    let examples_text = fs::read_to_string(&examples).map_err(io(&examples))?;
+    let examples_registered =
+        register_example(&examples_text, day).ok_or(ScaffoldError::NoRegistry(examples.clone()))?;
+    fs::write(&module, skeleton(day)).map_err(io(&module))?;
+    let mut written = vec![module];
+    // an example might already have been saved while reading the puzzle
+    if !example.exists() {
+        fs::write(&example, "").map_err(io(&example))?;
+        written.push(example);
+    }
+    fs::write(&lib, registered).map_err(io(&lib))?;
+    fs::write(&examples, examples_registered).map_err(io(&examples))?;
+    written.extend([lib, examples]);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIB: &str = "pub mod cli;
+pub mod error;
+
+days! {
+    1 => day1::Calories { generate },
+    3 => day3::Rucksacks { generate, reference },
+}
+
+pub fn find(day: u8) -> Result<&'static Day, Error> {
+    DAYS.iter()
+        .find(|d| d.number == day)
+        .ok_or(Error::Unimplemented(day))
+}
+";
+
+    const EXAMPLES: &str = r#"pub const EXAMPLES: &[Example] = &[
+    example!(1, "24000", "45000"),
+    Example {
+        day: 9,
+        variant: Some("larger"),
+        input: include_str!("examples/day9.larger.txt"),
+        part1: Some("88"),
+        part2: Some("36"),
+        parse: None,
+    },
+    example!(10, "13140", DAY10_PART2),
+];
+"#;
+
+    #[test]
+    fn test_register() {
+        let lib = register(LIB, 2).unwrap();
+        assert!(lib.contains("{ generate },\n    2 => day2::Puzzle,\n    3 => day3::"));
+        let lib = register(&lib, 4).unwrap();
+        assert!(lib.contains("reference },\n    4 => day4::Puzzle,\n}\n"));
+        assert_eq!(register("pub mod day1;\n", 2), None);
+        let examples = register_example(EXAMPLES, 5).unwrap();
+        assert!(examples
+            .contains("\"45000\"),\n    Example {\n        day: 5,\n        variant: None,"));
+        let examples = register_example(&examples, 11).unwrap();
+        assert!(examples.contains("DAY10_PART2),\n    Example {\n        day: 11,"));
+        assert!(skeleton(18).contains("const DAY: u8 = 18;"));
+        assert!(!skeleton(18).contains("todo!"));
+        assert!(examples.contains("include_str!(\"examples/day11.txt\")"));
+        // nothing is checked if the answers are left out, so they're there to be filled in
+        assert!(examples.contains("part1: Some(\"part 1's answer\"),"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join("aoc-2022-scaffold-test");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/examples")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/examples.rs"), EXAMPLES).unwrap();
+        fs::write(root.join("src/examples/day2.txt"), "saved already").unwrap();
+        let written = new_day(&root, 2).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                root.join("src/day2.rs"),
+                root.join("src/lib.rs"),
+                root.join("src/examples.rs")
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/examples/day2.txt")).unwrap(),
+            "saved already"
+        );
+        assert!(matches!(
+            new_day(&root, 2),
+            Err(Error::Scaffold(ScaffoldError::Exists(_)))
+        ));
+        assert!(matches!(
+            new_day(&root, 3),
+            Err(Error::Scaffold(ScaffoldError::Registered(3)))
+        ));
+    }
+}
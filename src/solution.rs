@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use crate::error::{Error, ParseError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Num(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Num(n) => write!(f, "{}", n),
+            Self::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Self::Num(n as i64)
+            }
+        })*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+/// A day's puzzle. The input is parsed once and both parts are answered from the parsed model,
+/// so any part can be run (or timed) on its own. Inputs that parse but leave a part without an
+/// answer give [`Error::NoAnswer`] rather than panicking.
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized;
+    fn part1(&self) -> Result<Answer, Error>;
+    fn part2(&self) -> Result<Answer, Error>;
+
+    fn part(&self, part: u8) -> Result<Answer, Error> {
+        match part {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => Err(Error::NoPart(part)),
+        }
+    }
+}
+
+/// Parses a day's input into its solution, see [`parse`].
+pub type Parser = fn(&str) -> Result<Box<dyn Solution>, ParseError>;
+
+/// Parses `input` as `S`, erasing the type so days can sit in one table.
+pub fn parse<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solution>, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Constant;
+
+    impl Solution for Constant {
+        fn parse(_: &str) -> Result<Self, ParseError> {
+            Ok(Self)
+        }
+
+        fn part1(&self) -> Result<Answer, Error> {
+            Ok(1.into())
+        }
+
+        fn part2(&self) -> Result<Answer, Error> {
+            Ok(2.into())
+        }
+    }
+
+    #[test]
+    fn test_part() {
+        assert_eq!(Constant.part(2).unwrap(), Answer::Num(2));
+        assert!(matches!(Constant.part(3), Err(Error::NoPart(3))));
+    }
+}
@@ -0,0 +1,243 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    answers::{self, Answers},
+    input::{self, InputError, MAIN_VARIANT},
+    pool,
+    solution::Parser,
+    Day,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// Nothing has been recorded to check the answer against.
+    Missing,
+    /// There is no input for the variant, so nothing was run.
+    NoInput,
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub status: Status,
+    pub answer: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+fn check_variant(
+    parse: Parser,
+    dir: &Path,
+    day: u8,
+    variant: &str,
+    answers: &Answers,
+) -> Vec<Check> {
+    let check = |part, status, answer| Check {
+        day,
+        part,
+        variant: variant.to_string(),
+        status,
+        answer,
+    };
+    let solution = match input::load_variant(dir, day, variant) {
+        // a panic is only this variant's problem, the other days still get checked
+        Ok(input) => match pool::catch(|| parse(&input)) {
+            Ok(parsed) => parsed.map_err(|e| e.to_string()),
+            Err(message) => Err(format!("panicked: {}", message)),
+        },
+        Err(InputError::Missing { .. }) => {
+            return vec![
+                check(1, Status::NoInput, None),
+                check(2, Status::NoInput, None),
+            ]
+        }
+        Err(e) => Err(e.to_string()),
+    };
+    let solution = match solution {
+        Ok(solution) => solution,
+        Err(e) => {
+            // the first line of a parse error is the summary, the rest points at the input
+            let e = e.lines().next().unwrap_or_default().to_string();
+            return vec![
+                check(1, Status::Error(e.clone()), None),
+                check(2, Status::Error(e), None),
+            ];
+        }
+    };
+    [1, 2]
+        .into_iter()
+        .map(|part| {
+            let answer = match pool::catch(|| solution.part(part)) {
+                Ok(Ok(answer)) => answer.to_string(),
+                Ok(Err(e)) => return check(part, Status::Error(e.to_string()), None),
+                Err(message) => {
+                    let status = Status::Error(format!("panicked: {}", message));
+                    return check(part, status, None);
+                }
+            };
+            let status = match answers.get(day, part, variant) {
+                None => Status::Missing,
+                Some(expected) if expected == answer => Status::Pass,
+                Some(expected) => Status::Fail {
+                    expected: expected.to_string(),
+                },
+            };
+            check(part, status, Some(answer))
+        })
+        .collect()
+}
+
+/// Runs both parts of every day on the real input in `dir`, plus any other variants that have
+/// answers recorded, and checks the results against `answers`.
+pub fn verify(days: &[Day], dir: &Path, answers: &Answers) -> Report {
+    let mut checks = Vec::new();
+    for &Day {
+        number: day, parse, ..
+    } in days
+    {
+        let mut variants = vec![MAIN_VARIANT];
+        variants.extend(
+            answers
+                .variants(day)
+                .into_iter()
+                .filter(|&v| v != MAIN_VARIANT),
+        );
+        for variant in variants {
+            checks.extend(check_variant(parse, dir, day, variant, answers));
+        }
+    }
+    Report { checks }
+}
+
+impl Report {
+    /// Whether anything gave a different answer than expected, or couldn't be run at all.
+    pub fn failed(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|c| matches!(c.status, Status::Fail { .. } | Status::Error(_)))
+    }
+
+    /// Fills in the answers that weren't known yet. Mismatches are left alone; those need a
+    /// human to decide which one is right. Gives how many were added.
+    pub fn record(&self, answers: &mut Answers) -> usize {
+        let mut added = 0;
+        for check in &self.checks {
+            if let (Status::Missing, Some(answer)) = (&check.status, &check.answer) {
+                answers.insert(check.day, check.part, &check.variant, answer.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+
+    fn count(&self, wanted: fn(&Status) -> bool) -> usize {
+        self.checks.iter().filter(|c| wanted(&c.status)).count()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variant_width = self
+            .checks
+            .iter()
+            .map(|c| c.variant.len())
+            .chain(["variant".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "day part {:vw$} result   answer",
+            "variant",
+            vw = variant_width
+        )?;
+        for check in &self.checks {
+            let answer = check
+                .answer
+                .as_deref()
+                .map(answers::escape)
+                .unwrap_or_default();
+            let (result, detail) = match &check.status {
+                Status::Pass => ("pass", answer),
+                Status::Fail { expected } => (
+                    "FAIL",
+                    format!("{} (expected {})", answer, answers::escape(expected)),
+                ),
+                Status::Missing => ("missing", answer),
+                Status::NoInput => ("no input", String::new()),
+                Status::Error(e) => ("ERROR", e.clone()),
+            };
+            let row = format!(
+                "{:>3} {:>4} {:vw$} {:8} {}",
+                check.day,
+                check.part,
+                check.variant,
+                result,
+                detail,
+                vw = variant_width
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} without input",
+            self.count(|s| *s == Status::Pass),
+            self.count(|s| matches!(s, Status::Fail { .. } | Status::Error(_))),
+            self.count(|s| *s == Status::Missing),
+            self.count(|s| *s == Status::NoInput),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        error::{Error, ParseError},
+        solution::{self, Answer, Solution},
+    };
+
+    /// Answers part 1 and panics on part 2.
+    struct Flaky;
+
+    impl Solution for Flaky {
+        fn parse(_: &str) -> Result<Self, ParseError> {
+            Ok(Flaky)
+        }
+        fn part1(&self) -> Result<Answer, Error> {
+            Ok("fine".into())
+        }
+        fn part2(&self) -> Result<Answer, Error> {
+            panic!("not today")
+        }
+    }
+
+    #[test]
+    fn test_panics_fail_only_their_part() {
+        let dir = std::env::temp_dir().join("aoc-2022-verify-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day1.txt"), "").unwrap();
+        std::fs::write(dir.join("day2.txt"), "").unwrap();
+        let days = &[
+            Day::new(1, solution::parse::<Flaky>),
+            Day::new(2, solution::parse::<Flaky>),
+        ];
+        let report = verify(days, &dir, &Answers::default());
+        let statuses: Vec<_> = report.checks.iter().map(|c| &c.status).collect();
+        let panicked = Status::Error("panicked: not today".to_string());
+        assert_eq!(
+            statuses,
+            [&Status::Missing, &panicked, &Status::Missing, &panicked]
+        );
+        assert!(report.failed());
+    }
+}
@@ -0,0 +1,213 @@
+//! `run <day> --watch`: keeping an eye on a day's inputs and running it again whenever one of
+//! them changes, to try out an edited example without going back to the command line. Files are
+//! polled for their modification time and size, which needs nothing beyond `std`.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    bench,
+    input::{self, Source},
+    runner::{DayRun, Failure, Outcome},
+    solution::Answer,
+};
+
+/// How long to wait between looking at the files.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// When a file was last modified and how big it was, `None` if it isn't there.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The files a day's answers come from: the input and any variants next to it, or just the one
+/// file if that's what was asked for.
+pub fn files(source: &Source, day: u8) -> Vec<PathBuf> {
+    match source {
+        Source::Dir(dir) => std::iter::once(input::day_path(dir, day))
+            .chain(
+                input::variants(dir, day)
+                    .iter()
+                    .map(|variant| input::variant_path(dir, day, variant)),
+            )
+            .collect(),
+        Source::File(path) => vec![path.clone()],
+        Source::Stdin | Source::Generated { .. } => Vec::new(),
+    }
+}
+
+pub struct Watcher {
+    day: u8,
+    source: Source,
+    /// What each file looked like the last time.
+    seen: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    pub fn new(day: u8, source: Source) -> Self {
+        Self {
+            day,
+            source,
+            seen: Vec::new(),
+        }
+    }
+
+    /// The files being watched, as of the last poll.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.seen.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Looks at the files again, giving the ones that changed, appeared or went away since last
+    /// time. The first time they all count as changed.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let now: Vec<(PathBuf, Stamp)> = files(&self.source, self.day)
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+        let changed = now.iter().filter(|&file| !self.seen.contains(file));
+        let gone = self
+            .seen
+            .iter()
+            .filter(|(path, _)| !now.iter().any(|(p, _)| p == path));
+        let changed = changed.chain(gone).map(|(path, _)| path.clone()).collect();
+        self.seen = now;
+        changed
+    }
+}
+
+/// The answers and timings of each run, and how they compare with the run of the same input in
+/// `previous`.
+pub fn report(runs: &[DayRun], previous: &[DayRun]) -> String {
+    let mut out = String::new();
+    for run in runs {
+        let before = previous
+            .iter()
+            .find(|before| before.input == run.input)
+            .and_then(|before| match &before.outcome {
+                Outcome::Solved { parse_time, parts } => Some((parse_time, parts)),
+                _ => None,
+            });
+        let _ = writeln!(out, "{}:", run.input);
+        let Outcome::Solved { parse_time, parts } = &run.outcome else {
+            for e in run.errors() {
+                let _ = writeln!(out, "  error: {}", e);
+            }
+            continue;
+        };
+        let _ = write!(out, "  parse  {:.2?}", parse_time);
+        if let Some((before, _)) = before {
+            let _ = write!(out, "  {}", bench::change(*parse_time, *before));
+        }
+        out.push('\n');
+        for part in parts {
+            let answer = match &part.answer {
+                Ok(answer) => answer,
+                Err(Failure::Error(e)) => {
+                    let _ = writeln!(out, "  part {}: {}", part.part, e);
+                    continue;
+                }
+                Err(Failure::Panicked(message)) => {
+                    let _ = writeln!(out, "  part {} panicked: {}", part.part, message);
+                    continue;
+                }
+            };
+            let _ = write!(out, "  part {} {:.2?}", part.part, part.time);
+            let before = before.and_then(|(_, parts)| parts.iter().find(|p| p.part == part.part));
+            if let Some(before) = before {
+                let _ = write!(out, "  {}", bench::change(part.time, before.time));
+            }
+            match answer {
+                // pictures read better starting on their own line
+                Answer::Text(text) if text.contains('\n') => {
+                    let _ = write!(out, ":\n{}", text);
+                }
+                answer => {
+                    let _ = write!(out, ": {}", answer);
+                }
+            }
+            match before.map(|before| &before.answer) {
+                Some(Ok(was)) if was != answer => {
+                    let _ = write!(out, " (was {})", was);
+                }
+                _ => (),
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runner::PartRun;
+
+    fn solved(input: &str, answer: i64, ms: u64) -> DayRun {
+        DayRun {
+            day: 1,
+            input: input.to_string(),
+            outcome: Outcome::Solved {
+                parse_time: Duration::from_millis(ms),
+                parts: vec![PartRun {
+                    part: 1,
+                    answer: Ok(Answer::Num(answer)),
+                    time: Duration::from_millis(ms),
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_poll() {
+        let dir = std::env::temp_dir().join("aoc-2022-watch-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day7.txt"), "1").unwrap();
+        let mut watcher = Watcher::new(7, Source::Dir(dir.clone()));
+        assert_eq!(watcher.poll(), vec![dir.join("day7.txt")]);
+        assert_eq!(watcher.poll(), Vec::<PathBuf>::new());
+        // a new example is picked up, and so is a change of size even within the same tick
+        fs::write(dir.join("day7.example.txt"), "2").unwrap();
+        fs::write(dir.join("day7.txt"), "12").unwrap();
+        assert_eq!(
+            watcher.poll(),
+            vec![dir.join("day7.txt"), dir.join("day7.example.txt")]
+        );
+        fs::remove_file(dir.join("day7.example.txt")).unwrap();
+        assert_eq!(watcher.poll(), vec![dir.join("day7.example.txt")]);
+        assert_eq!(
+            watcher.files().collect::<Vec<_>>(),
+            vec![dir.join("day7.txt")]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let first = report(&[solved("a.txt", 5, 2)], &[]);
+        assert_eq!(first, "a.txt:\n  parse  2.00ms\n  part 1 2.00ms: 5\n");
+        let again = report(&[solved("a.txt", 6, 3)], &[solved("a.txt", 5, 2)]);
+        assert_eq!(
+            again,
+            "a.txt:\n  parse  3.00ms  +50.0% (was 2.00ms)\n  part 1 3.00ms  +50.0% (was 2.00ms): 6 (was 5)\n"
+        );
+        let failed = DayRun {
+            day: 1,
+            input: "a.txt".to_string(),
+            outcome: Outcome::Panicked("oops".to_string()),
+        };
+        assert_eq!(
+            report(&[failed], &[solved("a.txt", 5, 2)]),
+            "a.txt:\n  error: day 1 panicked: oops\n"
+        );
+    }
+}